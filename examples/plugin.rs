@@ -0,0 +1,70 @@
+// A solver plugin (see src/plugin.rs) with its own take on day 1.  Build it
+// and copy it into the plugin directory to use it:
+//
+//   cargo build --release --example plugin
+//   mkdir -p plugins && cp target/release/examples/libplugin.so plugins/
+//   ./target/release/adventofcode-2018 --impl plugin 1 2
+
+use std::collections::HashSet;
+use std::ffi::CString;
+use std::os::raw::c_char;
+use std::slice;
+use std::sync::Mutex;
+
+// keeps the last answer alive until the next call, as the ABI requires
+static ANSWER: Mutex<Option<CString>> = Mutex::new(None);
+
+#[no_mangle]
+pub extern "C" fn aoc_abi_version() -> u32 {
+    1
+}
+
+#[no_mangle]
+pub extern "C" fn aoc_solves(day: u32, part: u32) -> i32 {
+    (day == 1 && (part == 1 || part == 2)) as i32
+}
+
+/// # Safety
+///
+/// `input` must point to `input_len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_solve(
+    day: u32,
+    part: u32,
+    input: *const c_char,
+    input_len: usize,
+) -> *const c_char {
+    let input = slice::from_raw_parts(input as *const u8, input_len);
+    let answer = match (day, std::str::from_utf8(input)) {
+        (1, Ok(input)) => frequency(part, input),
+        _ => None,
+    };
+    let mut saved = ANSWER.lock().unwrap();
+    *saved = answer.map(|answer| CString::new(answer.to_string()).unwrap());
+    match &*saved {
+        Some(answer) => answer.as_ptr(),
+        None => std::ptr::null(),
+    }
+}
+
+fn frequency(part: u32, input: &str) -> Option<i32> {
+    let changes: Vec<i32> = input
+        .lines()
+        .map(|line| line.parse().ok())
+        .collect::<Option<_>>()?;
+    match part {
+        1 => Some(changes.iter().sum()),
+        2 => {
+            let mut seen = HashSet::new();
+            let mut frequency = 0;
+            for change in changes.iter().cycle() {
+                if !seen.insert(frequency) {
+                    return Some(frequency);
+                }
+                frequency += change;
+            }
+            None
+        }
+        _ => None,
+    }
+}
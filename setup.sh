@@ -1,6 +1,5 @@
 #! /bin/sh
 
-year=2018
 day=$1
 
 if [ "$day" = "" ]; then
@@ -10,27 +9,20 @@ fi
 if ! git diff --exit-code > /dev/null; then
     echo There are uncommitted changes 2>&1
     exit 1
-elif [ -e input/day$day.txt ]; then
-    echo Already have day $day 2>&1
-    exit 1
 fi
 
-# download input (assumes w3m is already logged in)
-url=https://adventofcode.com/2018/day/$day/input > input/day$day.txt
-echo Fetching $url...
-mkdir -p input
-w3m $url > input/day$day.txt
-file input/day$day.txt
+# download input (needs AOC_SESSION or .session; respects aoc.toml)
+cargo run --release -q -- fetch $day || exit 1
 
 # setup new rust module
 echo "Creating src/day$day.rs"
 cat > src/day$day.rs <<EOF
-use super::{Part,Part::*};
+use super::{Answer, Part, Part::*};
 
-pub fn run(part: Part, input: &str) {
+pub fn run(part: Part, input: &str) -> Answer {
     match part {
-        One => println!(),
-        Two => println!(),
+        One => Answer::new(""),
+        Two => Answer::new(""),
     }
 }
 
@@ -49,4 +41,6 @@ mod tests {
 EOF
 echo "Editing src/main.rs"
 echo "mod day$day;" >> src/main.rs
-sed -i "0,/.*not implemented.*/s//        $day => day${day}::run(part, \&input),\n&/" src/main.rs
+echo "Editing src/registry.rs"
+# only the VARIANTS table, not the other tables that end the same way
+sed -i "/^static VARIANTS/,/^];/s/^];/    Variant { day: $day, name: \"default\", parts: BOTH, run: Builtin(day${day}::run), stream: None },\n];/" src/registry.rs
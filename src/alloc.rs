@@ -0,0 +1,143 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering::Relaxed};
+
+// Wraps the system allocator to count allocations and track the peak number
+// of bytes in use.  Counting is off until enable() is called (--mem), so
+// normal runs only pay for one extra load per allocation.
+struct Counting;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() && ENABLED.load(Relaxed) {
+            grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        if ENABLED.load(Relaxed) {
+            shrink(layout.size());
+        }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() && ENABLED.load(Relaxed) {
+            grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() && ENABLED.load(Relaxed) {
+            shrink(layout.size());
+            grow(new_size);
+        }
+        new_ptr
+    }
+}
+
+fn grow(size: usize) {
+    let current = CURRENT.fetch_add(size, Relaxed) + size;
+    PEAK.fetch_max(current, Relaxed);
+    COUNT.fetch_add(1, Relaxed);
+}
+
+// memory allocated before counting was enabled can be freed afterwards, so
+// don't let the count go below zero.
+fn shrink(size: usize) {
+    let _ = CURRENT.fetch_update(Relaxed, Relaxed, |current| {
+        Some(current.saturating_sub(size))
+    });
+}
+
+pub fn enable() {
+    ENABLED.store(true, Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Relaxed)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Usage {
+    pub peak: usize,
+    pub allocations: usize,
+}
+
+impl fmt::Display for Usage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} peak, {} allocs",
+            format_bytes(self.peak),
+            self.allocations
+        )
+    }
+}
+
+// Measure allocations made while running `f`.  Peak is relative to what was
+// already in use when it started.  Returns None if counting isn't enabled.
+pub fn measure<T, F: FnOnce() -> T>(f: F) -> (T, Option<Usage>) {
+    if !is_enabled() {
+        return (f(), None);
+    }
+    let base = CURRENT.load(Relaxed);
+    PEAK.store(base, Relaxed);
+    let count = COUNT.load(Relaxed);
+    let result = f();
+    let usage = Usage {
+        peak: PEAK.load(Relaxed).saturating_sub(base),
+        allocations: COUNT.load(Relaxed) - count,
+    };
+    (result, Some(usage))
+}
+
+pub fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!("512 B", format_bytes(512));
+        assert_eq!("1.5 KiB", format_bytes(1536));
+        assert_eq!("3.0 MiB", format_bytes(3 * 1024 * 1024));
+    }
+
+    #[test]
+    fn test_measure() {
+        enable();
+        let (v, usage) = measure(|| vec![0u8; 1 << 20]);
+        let usage = usage.unwrap();
+        assert_eq!(1 << 20, v.len());
+        assert!(usage.peak >= 1 << 20);
+        assert!(usage.allocations >= 1);
+    }
+}
@@ -0,0 +1,194 @@
+use super::submit;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use toml::{Table, Value};
+
+// Settings from the optional aoc.toml, e.g.
+//
+//   input_dir = "input"          # where dayN.txt files are read from
+//   output_dir = "output"        # where images and other artifacts go
+//   plugin_dir = "plugins"       # shared libraries with extra solvers
+//   profile = "tim"              # read inputs from input_dir/tim/ instead
+//   endpoint = "https://adventofcode.com"
+//   trim_lines = true            # strip trailing whitespace from input lines
+//
+//   [day7]                       # default parameters for a day's solver
+//   workers = 5
+//
+// Anything not given keeps the value the code always used.
+
+pub const FILENAME: &str = "aoc.toml";
+
+// parameters that are counts or sizes, read with param_usize()
+const COUNTS: &[(u32, &str)] = &[
+    (2, "distance"),
+    (2, "mismatches"),
+    (6, "limit"),
+    (7, "workers"),
+    (7, "base_time"),
+    (20, "doors"),
+];
+
+#[derive(Debug, PartialEq)]
+pub struct Config {
+    pub input_dir: PathBuf,
+    pub output_dir: PathBuf,
+    pub plugin_dir: PathBuf,
+    pub profile: Option<String>,
+    pub endpoint: String,
+    pub trim_lines: bool,
+    params: HashMap<u32, HashMap<String, i64>>,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            input_dir: PathBuf::from("input"),
+            output_dir: PathBuf::from("."),
+            plugin_dir: PathBuf::from("plugins"),
+            profile: None,
+            endpoint: submit::DEFAULT_ENDPOINT.to_string(),
+            trim_lines: false,
+            params: HashMap::new(),
+        }
+    }
+}
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+impl Config {
+    pub fn parse(s: &str) -> Result<Config, String> {
+        let table: Table = s.parse().map_err(|e| format!("{}", e))?;
+        let mut config = Config::default();
+        for (key, value) in table {
+            match (key.as_str(), value) {
+                ("input_dir", Value::String(s)) => config.input_dir = PathBuf::from(s),
+                ("output_dir", Value::String(s)) => config.output_dir = PathBuf::from(s),
+                ("plugin_dir", Value::String(s)) => config.plugin_dir = PathBuf::from(s),
+                ("profile", Value::String(s)) => config.profile = Some(s),
+                ("endpoint", Value::String(s)) => config.endpoint = s,
+                ("trim_lines", Value::Boolean(b)) => config.trim_lines = b,
+                (day, Value::Table(params)) if day.starts_with("day") => {
+                    let day = day[3..]
+                        .parse()
+                        .map_err(|_| format!("{} is not a day", day))?;
+                    let mut values = HashMap::new();
+                    for (name, value) in params {
+                        match value {
+                            Value::Integer(i) if i < 0 && COUNTS.contains(&(day, &name)) => {
+                                return Err(format!("day{}.{} can't be negative", day, name))
+                            }
+                            Value::Integer(i) => values.insert(name, i),
+                            _ => return Err(format!("day{}.{} must be an integer", day, name)),
+                        };
+                    }
+                    config.params.insert(day, values);
+                }
+                (key, _) => return Err(format!("unknown or invalid setting {}", key)),
+            }
+        }
+        Ok(config)
+    }
+
+    // a missing file gives the default config
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Config, String> {
+        match fs::read_to_string(path.as_ref()) {
+            Ok(s) => Config::parse(&s).map_err(|e| format!("{}: {}", path.as_ref().display(), e)),
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(e) => Err(format!("{}: {}", path.as_ref().display(), e)),
+        }
+    }
+
+    // directory holding this profile's inputs and answer history
+    pub fn profile_dir(&self) -> PathBuf {
+        match &self.profile {
+            Some(profile) => self.input_dir.join(profile),
+            None => self.input_dir.clone(),
+        }
+    }
+
+    pub fn input_path(&self, day: u32) -> PathBuf {
+        self.profile_dir().join(format!("day{}.txt", day))
+    }
+
+    // where to write an artifact such as an image, creating the directory
+    pub fn output_path(&self, filename: &str) -> PathBuf {
+        fs::create_dir_all(&self.output_dir).unwrap();
+        self.output_dir.join(filename)
+    }
+
+    pub fn param(&self, day: u32, name: &str) -> Option<i64> {
+        self.params.get(&day)?.get(name).copied()
+    }
+
+    // a parameter listed in COUNTS, which parse() has made sure isn't
+    // negative
+    pub fn param_usize(&self, day: u32, name: &str) -> Option<usize> {
+        debug_assert!(
+            COUNTS.contains(&(day, name)),
+            "day{}.{} isn't a count",
+            day,
+            name
+        );
+        self.param(day, name).map(|value| value as usize)
+    }
+}
+
+// install the config for the rest of the run; only the first call counts
+pub fn set(config: Config) {
+    let _ = CONFIG.set(config);
+}
+
+// the installed config, or the default one (as in tests)
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+// a day's parameter from the config, or its usual value
+pub fn param(day: u32, name: &str, default: i64) -> i64 {
+    get().param(day, name).unwrap_or(default)
+}
+
+pub fn param_usize(day: u32, name: &str, default: usize) -> usize {
+    get().param_usize(day, name).unwrap_or(default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let config = Config::parse(
+            "input_dir = \"inputs\"\nprofile = \"tim\"\n\n[day7]\nworkers = 2\nbase_time = 0\n",
+        )
+        .unwrap();
+        assert_eq!(PathBuf::from("inputs/tim/day3.txt"), config.input_path(3));
+        assert_eq!(PathBuf::from("."), config.output_dir);
+        assert_eq!(Some(2), config.param(7, "workers"));
+        assert_eq!(Some(0), config.param(7, "base_time"));
+        assert_eq!(None, config.param(6, "limit"));
+        assert_eq!(Ok(Config::default()), Config::parse(""));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Config::parse("input = \"x\"").is_err());
+        assert!(Config::parse("input_dir = 3").is_err());
+        assert!(Config::parse("[dayx]\na = 1").is_err());
+        assert!(Config::parse("[day7]\nworkers = \"five\"").is_err());
+        assert!(Config::parse("[day7]\nworkers = -1").is_err());
+        assert!(Config::parse("[day17]\nimage = -1").is_ok());
+    }
+
+    #[test]
+    fn test_param_usize() {
+        let config = Config::parse("[day7]\nworkers = 2\nbase_time = 0\n").unwrap();
+        assert_eq!(Some(2), config.param_usize(7, "workers"));
+        assert_eq!(Some(0), config.param_usize(7, "base_time"));
+        assert_eq!(None, config.param_usize(6, "limit"));
+    }
+}
@@ -0,0 +1,410 @@
+use super::registry::Variant;
+use super::{alloc, config, measure, read_input, registry, submit, watch, Part};
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use std::fs;
+use std::io::{self, Write};
+use std::time::Duration;
+
+// An interactive table of every day: whether it's implemented and has an
+// input, the last answer sent for each part until the day is run, then the
+// answers and timings from this session, and whether they match the answers
+// the server accepted (see submit.rs).  The selected day can be run,
+// benchmarked or, for days that have one, visualized.
+
+const PARTS: [Part; 2] = [Part::One, Part::Two];
+const BENCH_RUNS: u32 = 10;
+const ANSWER_WIDTH: usize = 16;
+const HELP: &str = "up/down select  r run  b bench  v visualize  q quit";
+
+enum Outcome {
+    // from the history, not yet run this session
+    Submitted(String),
+    Solved(String, Duration),
+    Failed(String),
+}
+
+struct Day {
+    day: u32,
+    implemented: bool,
+    input_size: Option<u64>,
+    expected: [Option<String>; 2],
+    outcomes: [Option<Outcome>; 2],
+}
+
+impl Day {
+    fn load(day: u32, history: &submit::History) -> Day {
+        Day {
+            day,
+            implemented: !registry::variants(day, Part::One).is_empty(),
+            input_size: fs::metadata(config::get().input_path(day))
+                .ok()
+                .map(|m| m.len()),
+            expected: PARTS.map(|part| history.right_answer(day, part).map(String::from)),
+            outcomes: PARTS.map(|part| {
+                history
+                    .last_answer(day, part)
+                    .map(|answer| Outcome::Submitted(answer.to_string()))
+            }),
+        }
+    }
+
+    // "ok" or "WRONG" once there's an answer to compare with an accepted
+    // one, "?" if nothing has been accepted yet
+    fn verdict(&self, i: usize) -> &'static str {
+        match &self.outcomes[i] {
+            Some(Outcome::Submitted(answer) | Outcome::Solved(answer, _)) => {
+                match &self.expected[i] {
+                    Some(expected) if answer == expected => "ok",
+                    Some(_) => "WRONG",
+                    None => "?",
+                }
+            }
+            Some(Outcome::Failed(_)) => "ERROR",
+            None => "",
+        }
+    }
+
+    fn row(&self) -> String {
+        let mut row = format!(
+            "{:>3}  {:<4} {:>9}",
+            self.day,
+            if self.implemented { "yes" } else { "-" },
+            match self.input_size {
+                Some(size) => alloc::format_bytes(size as usize),
+                None => "-".to_string(),
+            }
+        );
+        for i in 0..PARTS.len() {
+            let (answer, time) = match &self.outcomes[i] {
+                Some(Outcome::Submitted(answer)) => {
+                    (truncate(answer, ANSWER_WIDTH), "sent".to_string())
+                }
+                Some(Outcome::Solved(answer, elapsed)) => (
+                    truncate(answer, ANSWER_WIDTH),
+                    format!("{:.3}s", elapsed.as_secs_f64()),
+                ),
+                Some(Outcome::Failed(e)) => (
+                    truncate(e.lines().last().unwrap_or(""), ANSWER_WIDTH),
+                    "".to_string(),
+                ),
+                None => ("".to_string(), "".to_string()),
+            };
+            row.push_str(&format!(
+                "  {:<width$} {:>8} {:<5}",
+                answer,
+                time,
+                self.verdict(i),
+                width = ANSWER_WIDTH
+            ));
+        }
+        if registry::visualization(self.day).is_some() {
+            row.push_str("  v");
+        }
+        row
+    }
+}
+
+fn header() -> String {
+    format!(
+        "Day  Impl     Input  {:<width$} {:>8} {:<5}  {:<width$} {:>8} {:<5}",
+        "Part 1",
+        "Time",
+        "Check",
+        "Part 2",
+        "Time",
+        "Check",
+        width = ANSWER_WIDTH
+    )
+}
+
+fn truncate(s: &str, width: usize) -> String {
+    if s.chars().count() <= width {
+        s.to_string()
+    } else {
+        s.chars().take(width - 1).chain(Some('…')).collect()
+    }
+}
+
+// puts the terminal back however we leave
+struct Screen;
+
+impl Screen {
+    fn enter() -> io::Result<Screen> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(Screen)
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+struct Dashboard {
+    days: Vec<Day>,
+    selected: usize,
+    message: String,
+}
+
+pub fn run() -> io::Result<()> {
+    let path = config::get().profile_dir().join("submissions.tsv");
+    let history = submit::History::load(path)?;
+    let mut dashboard = Dashboard {
+        days: (1..=25).map(|day| Day::load(day, &history)).collect(),
+        selected: 0,
+        message: String::new(),
+    };
+    watch::catch_panics();
+    let _screen = Screen::enter()?;
+    loop {
+        dashboard.draw()?;
+        let key = match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => key.code,
+            _ => continue,
+        };
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Up | KeyCode::Char('k') => {
+                dashboard.selected = dashboard.selected.saturating_sub(1)
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                dashboard.selected = (dashboard.selected + 1).min(dashboard.days.len() - 1)
+            }
+            KeyCode::Char('r') => dashboard.run(1),
+            KeyCode::Char('b') => dashboard.run(BENCH_RUNS),
+            KeyCode::Char('v') => dashboard.visualize()?,
+            _ => (),
+        }
+    }
+}
+
+impl Dashboard {
+    fn draw(&self) -> io::Result<()> {
+        let mut out = io::stdout();
+        let (width, height) = terminal::size()?;
+        let width = width as usize;
+        // header, days, a blank line, the message and help
+        let visible = (height as usize).saturating_sub(4).max(1);
+        let first = (self.selected + 1).saturating_sub(visible);
+
+        queue!(out, Clear(ClearType::All), MoveTo(0, 0))?;
+        queue!(out, Print(truncate(&header(), width)))?;
+        for (y, day) in self.days.iter().enumerate().skip(first).take(visible) {
+            queue!(out, MoveTo(0, (y - first + 1) as u16))?;
+            if y == self.selected {
+                queue!(out, SetAttribute(Attribute::Reverse))?;
+            }
+            queue!(
+                out,
+                Print(format!(
+                    "{:<width$}",
+                    truncate(&day.row(), width),
+                    width = width
+                )),
+                SetAttribute(Attribute::Reset)
+            )?;
+        }
+        queue!(
+            out,
+            MoveTo(0, height.saturating_sub(2)),
+            Print(truncate(&self.message, width)),
+            MoveTo(0, height.saturating_sub(1)),
+            Print(truncate(HELP, width))
+        )?;
+        out.flush()
+    }
+
+    fn show_message(&mut self, message: String) -> io::Result<()> {
+        self.message = message;
+        self.draw()
+    }
+
+    // run both parts of the selected day, recording the mean time
+    fn run(&mut self, runs: u32) {
+        let day = self.days[self.selected].day;
+        if let Err(e) = self.try_run(day, runs) {
+            self.message = e;
+        }
+    }
+
+    fn try_run(&mut self, day: u32, runs: u32) -> Result<(), String> {
+        // day 25 has no second puzzle, so run whichever parts there are
+        registry::find(day, Part::One, None)?;
+        let variants: Vec<(usize, Part, &'static Variant)> = PARTS
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &part)| Some((i, part, registry::find(day, part, None).ok()?)))
+            .collect();
+        if self.days[self.selected].input_size.is_none() {
+            return Err(format!("No input for day {}", day));
+        }
+        let input = watch::guard(|| read_input(day))?;
+        let mut messages = vec![];
+        for (i, part, variant) in variants {
+            let _ = self.show_message(format!("Running day {} part {}...", day, part));
+            let mut total = Duration::new(0, 0);
+            let mut answer = Ok(String::new());
+            for _ in 0..runs {
+                let (result, elapsed, _usage) =
+                    measure(|| watch::guard(|| variant.solve(part, &input)).and_then(|r| r));
+                total += elapsed;
+                answer = result.map(|answer| answer.value);
+                if answer.is_err() {
+                    break;
+                }
+            }
+            let outcome = match answer {
+                Ok(answer) => Some(Outcome::Solved(answer, total / runs)),
+                Err(e) => {
+                    messages.push(format!("Part {}: {}", part, e.replace('\n', " ")));
+                    Some(Outcome::Failed(e))
+                }
+            };
+            self.days[self.selected].outcomes[i] = outcome;
+        }
+        self.message = if messages.is_empty() {
+            match runs {
+                1 => format!("Ran day {}", day),
+                _ => format!("Day {}: mean of {} runs", day, runs),
+            }
+        } else {
+            messages.join("; ")
+        };
+        Ok(())
+    }
+
+    // page through the selected day's picture until q
+    fn visualize(&mut self) -> io::Result<()> {
+        let day = self.days[self.selected].day;
+        let visualize = match registry::visualization(day) {
+            Some(visualize) => visualize,
+            None => {
+                self.message = format!("Day {} has no visualization", day);
+                return Ok(());
+            }
+        };
+        if self.days[self.selected].input_size.is_none() {
+            self.message = format!("No input for day {}", day);
+            return Ok(());
+        }
+        self.show_message(format!("Drawing day {}...", day))?;
+        let picture = match watch::guard(|| visualize(&read_input(day))) {
+            Ok(picture) => picture,
+            Err(e) => {
+                self.message = e.replace('\n', " ");
+                return Ok(());
+            }
+        };
+        let lines: Vec<&str> = picture.lines().collect();
+
+        let mut out = io::stdout();
+        let mut top = 0;
+        loop {
+            let (width, height) = terminal::size()?;
+            let page = (height as usize).saturating_sub(1).max(1);
+            let last = lines.len().saturating_sub(page);
+            top = top.min(last);
+            queue!(out, Clear(ClearType::All))?;
+            for (y, line) in lines.iter().skip(top).take(page).enumerate() {
+                queue!(
+                    out,
+                    MoveTo(0, y as u16),
+                    Print(truncate(line, width as usize))
+                )?;
+            }
+            queue!(
+                out,
+                MoveTo(0, height.saturating_sub(1)),
+                SetAttribute(Attribute::Reverse),
+                Print(format!(
+                    "Day {} lines {}-{} of {}  up/down/pgup/pgdn scroll  q back",
+                    day,
+                    top + 1,
+                    (top + page).min(lines.len()),
+                    lines.len()
+                )),
+                SetAttribute(Attribute::Reset)
+            )?;
+            out.flush()?;
+
+            let key = match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => key.code,
+                _ => continue,
+            };
+            match key {
+                KeyCode::Char('q') | KeyCode::Esc => break,
+                KeyCode::Up | KeyCode::Char('k') => top = top.saturating_sub(1),
+                KeyCode::Down | KeyCode::Char('j') => top += 1,
+                KeyCode::PageUp => top = top.saturating_sub(page),
+                KeyCode::PageDown | KeyCode::Char(' ') => top += page,
+                KeyCode::Home => top = 0,
+                KeyCode::End => top = last,
+                _ => (),
+            }
+        }
+        self.message.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(expected: Option<&str>, outcome: Option<Outcome>) -> Day {
+        Day {
+            day: 3,
+            implemented: true,
+            input_size: Some(2048),
+            expected: [expected.map(String::from), None],
+            outcomes: [outcome, None],
+        }
+    }
+
+    #[test]
+    fn test_verdict() {
+        let solved = |answer: &str| Some(Outcome::Solved(answer.to_string(), Duration::new(0, 0)));
+        assert_eq!("", day(Some("42"), None).verdict(0));
+        assert_eq!("ok", day(Some("42"), solved("42")).verdict(0));
+        assert_eq!("WRONG", day(Some("42"), solved("41")).verdict(0));
+        assert_eq!("?", day(None, solved("42")).verdict(0));
+        let sent = |answer: &str| Some(Outcome::Submitted(answer.to_string()));
+        assert_eq!("ok", day(Some("42"), sent("42")).verdict(0));
+        assert_eq!("WRONG", day(Some("42"), sent("41")).verdict(0));
+        let failed = Some(Outcome::Failed("boom".to_string()));
+        assert_eq!("ERROR", day(Some("42"), failed).verdict(0));
+    }
+
+    #[test]
+    fn test_row() {
+        let solved = day(
+            Some("42"),
+            Some(Outcome::Solved(
+                "42".to_string(),
+                Duration::from_millis(1500),
+            )),
+        );
+        assert_eq!(
+            "  3  yes    2.0 KiB  42                 1.500s ok",
+            solved.row().trim_end()
+        );
+        let sent = day(Some("42"), Some(Outcome::Submitted("42".to_string())));
+        assert_eq!(
+            "  3  yes    2.0 KiB  42                   sent ok",
+            sent.row().trim_end()
+        );
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!("abc", truncate("abc", 3));
+        assert_eq!("ab…", truncate("abcd", 3));
+    }
+}
@@ -1,41 +1,90 @@
-use super::{Part, Part::*};
-use std::collections::HashSet;
+use super::{Answer, Part, Part::*};
+use std::collections::{HashMap, HashSet};
+use std::io::BufRead;
 
-pub fn run(part: Part, input: &str) {
+pub fn run(part: Part, input: &str) -> Result<Answer, String> {
+    stream(part, &mut input.as_bytes())
+}
+
+// part 1 never holds more than a line.  Part 2 has to keep every change to
+// cycle through them, so it needs memory in proportion to the input, just
+// not the text the changes came from.
+pub fn stream(part: Part, input: &mut dyn BufRead) -> Result<Answer, String> {
     let input = parse_input(input);
-    println!(
-        "{}",
-        match part {
-            One => sum_lines(input),
-            Two => first_reached_twice(input),
-        }
-    );
+    Ok(Answer::new(match part {
+        One => sum_lines(input),
+        Two => first_reached_twice(input.collect())?,
+    }))
 }
 
-fn sum_lines(input: Vec<i32>) -> i32 {
-    input.iter().sum()
+fn sum_lines<I: IntoIterator<Item = i32>>(input: I) -> i32 {
+    input.into_iter().sum()
 }
 
-fn first_reached_twice(input: Vec<i32>) -> i32 {
-    let mut sum = 0;
+// Frequency after pass k and j more changes is k * drift + prefix[j], where
+// drift is the sum of all the changes and prefix[j] the sum of the first j.
+// Unless the first pass repeats itself, a frequency can only come round again
+// from a prefix in the same residue class modulo the drift, moving towards
+// the next one along in the direction of the drift.  The earliest such
+// arrival is the first repeat, found without simulating any passes.
+fn first_reached_twice(input: Vec<i32>) -> Result<i32, String> {
+    let mut prefix = Vec::with_capacity(input.len());
     let mut seen = HashSet::new();
-    seen.insert(sum);
+    let mut sum = 0;
+    for change in input.iter() {
+        if !seen.insert(sum) {
+            return Ok(sum);
+        }
+        prefix.push(sum);
+        sum += change;
+    }
+    let drift = sum;
+    if drift == 0 {
+        // back to where it started after one pass, unless there was no pass
+        return if input.is_empty() {
+            Err("no repeat: there are no changes".to_string())
+        } else {
+            Ok(0)
+        };
+    }
 
-    for i in input.iter().cycle() {
-        sum += i;
-        if seen.contains(&sum) {
-            return sum;
+    // measured along the drift, so every frequency only goes up
+    let step = drift.abs() as i64;
+    let mut classes: HashMap<i64, Vec<(i64, usize)>> = HashMap::new();
+    for (j, &p) in prefix.iter().enumerate() {
+        let along = p as i64 * drift.signum() as i64;
+        classes
+            .entry(along.rem_euclid(step))
+            .or_default()
+            .push((along, j));
+    }
+    // (changes applied, frequency) of the earliest repeat
+    let mut first: Option<(i64, i32)> = None;
+    for class in classes.values_mut() {
+        class.sort_unstable();
+        for pair in class.windows(2) {
+            let ((from, j), (to, k)) = (pair[0], pair[1]);
+            let passes = (to - from) / step;
+            let time = passes * prefix.len() as i64 + j as i64;
+            if first.is_none_or(|(t, _)| time < t) {
+                first = Some((time, prefix[k]));
+            }
         }
-        seen.insert(sum);
     }
-    unreachable!();
+    first
+        .map(|(_, frequency)| frequency)
+        .ok_or_else(|| format!("no repeat: every frequency drifts by {} each pass", drift))
+}
+
+// parse_input() is lazy, so this collects everything it would stream
+pub fn parse(input: &str) {
+    std::hint::black_box(parse_input(&mut input.as_bytes()).collect::<Vec<_>>());
 }
 
-fn parse_input(input: &str) -> Vec<i32> {
+fn parse_input(input: &mut dyn BufRead) -> impl Iterator<Item = i32> + '_ {
     input
         .lines()
-        .filter_map(|line| line.parse::<i32>().ok())
-        .collect()
+        .filter_map(|line| line.unwrap().trim().parse::<i32>().ok())
 }
 
 #[test]
@@ -47,8 +96,65 @@ fn test_sum_lines() {
 
 #[test]
 fn test_first_reached_twice() {
-    assert_eq!(0, first_reached_twice(vec![1, -1]));
-    assert_eq!(10, first_reached_twice(vec![3, 3, 4, -2, -4]));
-    assert_eq!(5, first_reached_twice(vec![-6, 3, 8, 5, -6]));
-    assert_eq!(14, first_reached_twice(vec![7, 7, -2, -7, -4]));
+    assert_eq!(Ok(0), first_reached_twice(vec![1, -1]));
+    assert_eq!(Ok(10), first_reached_twice(vec![3, 3, 4, -2, -4]));
+    assert_eq!(Ok(5), first_reached_twice(vec![-6, 3, 8, 5, -6]));
+    assert_eq!(Ok(14), first_reached_twice(vec![7, 7, -2, -7, -4]));
+    assert!(first_reached_twice(vec![1, 1]).is_err());
+    assert!(first_reached_twice(vec![]).is_err());
+}
+
+#[test]
+fn test_run() {
+    assert_eq!("2", run(One, "+1\n+1\n").unwrap().value);
+    assert_eq!("0", run(Two, "+1\n-1\n").unwrap().value);
+    assert!(run(Two, "+1\n+1\n").is_err());
+}
+
+#[test]
+fn test_stream_windows() {
+    // streamed as main does it, through input::open which only skips the
+    // byte order mark, so the CRLFs and blank line are left to the parser
+    use crate::input::{as_windows, open};
+    let path = std::env::temp_dir().join(format!("aoc-day1-{}.txt", std::process::id()));
+    std::fs::write(&path, as_windows("+3\n+3\n+4\n-2\n-4")).unwrap();
+    assert_eq!("4", stream(One, &mut open(&path).unwrap()).unwrap().value);
+    assert_eq!("10", stream(Two, &mut open(&path).unwrap()).unwrap().value);
+    std::fs::remove_file(&path).unwrap();
+}
+
+// the original approach, running passes until a frequency repeats or it's
+// clear none will
+#[cfg(test)]
+fn first_reached_twice_by_passes(input: &[i32], passes: usize) -> Option<i32> {
+    let mut sum = 0;
+    let mut seen = HashSet::new();
+    seen.insert(sum);
+    for i in input.iter().cycle().take(input.len() * passes) {
+        sum += i;
+        if !seen.insert(sum) {
+            return Some(sum);
+        }
+    }
+    None
+}
+
+#[test]
+fn test_first_reached_twice_by_passes() {
+    use crate::random::XorShift;
+    // changes from a small xorshift generator, small enough that any repeat
+    // comes within a few hundred passes
+    let mut random = XorShift::new(2018);
+    let mut next = |n| random.below(n);
+    for _ in 0..1000 {
+        let len = 1 + next(8) as usize;
+        let input: Vec<i32> = (0..len).map(|_| next(21) as i32 - 10).collect();
+        let expected = first_reached_twice_by_passes(&input, 500);
+        assert_eq!(
+            expected,
+            first_reached_twice(input.clone()).ok(),
+            "{:?}",
+            input
+        );
+    }
 }
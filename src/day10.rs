@@ -1,17 +1,27 @@
-use super::{Part, Part::*};
+use super::{config, Answer, Part, Part::*};
 use image::ImageBuffer;
 use regex::Regex;
+use std::path::Path;
 
-pub fn run(part: Part, input: &str) {
+pub fn run(part: Part, input: &str) -> Answer {
     match part {
         One => {
-            find_message(&mut parse_input(input), true);
-            println!("Wrote day10.png containing answer.");
+            let path = config::get().output_path("day10.png");
+            let seconds = find_message(&mut parse_input(input), Some(&path));
+            Answer::new(format!("Wrote {} containing answer.", path.display()))
+                .detail("seconds", seconds)
         }
-        Two => println!("{}", find_message(&mut parse_input(input), false)),
+        Two => Answer::new(find_message(&mut parse_input(input), None)),
     }
 }
 
+// the message as text, for the dashboard
+pub fn visualize(input: &str) -> String {
+    let mut stars = parse_input(input);
+    find_message(&mut stars, None);
+    to_text(&stars)
+}
+
 #[derive(Clone, Copy, Debug)]
 struct Point {
     x: i64,
@@ -25,7 +35,7 @@ struct Star {
     dy: i64,
 }
 
-fn find_message(stars: &mut Vec<Star>, write_image: bool) -> i32 {
+fn find_message(stars: &mut [Star], image_path: Option<&Path>) -> i32 {
     let mut s = 0;
     let mut last_area = area(stars);
     loop {
@@ -39,27 +49,27 @@ fn find_message(stars: &mut Vec<Star>, write_image: bool) -> i32 {
         }
         last_area = a;
     }
-    if write_image {
-        to_image(stars);
+    if let Some(path) = image_path {
+        to_image(stars, path);
     }
     s
 }
 
-fn tick_forward(stars: &mut Vec<Star>) {
+fn tick_forward(stars: &mut [Star]) {
     for star in stars.iter_mut() {
         star.point.x += star.dx;
         star.point.y += star.dy;
     }
 }
 
-fn tick_backwards(stars: &mut Vec<Star>) {
+fn tick_backwards(stars: &mut [Star]) {
     for star in stars.iter_mut() {
         star.point.x -= star.dx;
         star.point.y -= star.dy;
     }
 }
 
-fn to_image(stars: &Vec<Star>) {
+fn to_image(stars: &[Star], path: &Path) {
     let (min, max) = boxsize(stars);
     let width = max.x - min.x + 3; // 2 is border
     let height = max.y - min.y + 3;
@@ -69,15 +79,28 @@ fn to_image(stars: &Vec<Star>) {
         let y = star.point.y - min.y + 1;
         img.put_pixel(x as u32, y as u32, image::Luma([255u8]));
     }
-    img.save("day10.png").unwrap();
+    img.save(path).unwrap();
+}
+
+fn to_text(stars: &[Star]) -> String {
+    let (min, max) = boxsize(stars);
+    let width = (max.x - min.x + 1) as usize;
+    let height = (max.y - min.y + 1) as usize;
+    let mut grid = vec![vec!['.'; width]; height];
+    for star in stars.iter() {
+        grid[(star.point.y - min.y) as usize][(star.point.x - min.x) as usize] = '#';
+    }
+    grid.iter()
+        .map(|row| row.iter().collect::<String>() + "\n")
+        .collect()
 }
 
-fn area(stars: &Vec<Star>) -> u64 {
+fn area(stars: &[Star]) -> u64 {
     let (min, max) = boxsize(stars);
     ((max.x - min.x + 1).abs() * (max.y - min.y + 1).abs()) as u64
 }
 
-fn boxsize(stars: &Vec<Star>) -> (Point, Point) {
+fn boxsize(stars: &[Star]) -> (Point, Point) {
     let mut min = stars.first().unwrap().point;
     let mut max = stars.first().unwrap().point;
     for star in stars.iter().skip(1) {
@@ -95,6 +118,10 @@ fn boxsize(stars: &Vec<Star>) -> (Point, Point) {
     (min, max)
 }
 
+pub fn parse(input: &str) {
+    std::hint::black_box(parse_input(input));
+}
+
 fn parse_input(input: &str) -> Vec<Star> {
     let re =
         Regex::new(r"^position=<\s*(-?\d+),\s*(-?\d+)> velocity=<\s*(-?\d+),\s*(-?\d+)>$").unwrap();
@@ -114,3 +141,53 @@ fn parse_input(input: &str) -> Vec<Star> {
     }
     stars
 }
+
+#[cfg(test)]
+fn test_input() -> &'static str {
+    "position=< 9,  1> velocity=< 0,  2>
+position=< 7,  0> velocity=<-1,  0>
+position=< 3, -2> velocity=<-1,  1>
+position=< 6, 10> velocity=<-2, -1>
+position=< 2, -4> velocity=< 2,  2>
+position=<-6, 10> velocity=< 2, -2>
+position=< 1,  8> velocity=< 1, -1>
+position=< 1,  7> velocity=< 1,  0>
+position=<-3, 11> velocity=< 1, -2>
+position=< 7,  6> velocity=<-1, -1>
+position=<-2,  3> velocity=< 1,  0>
+position=<-4,  3> velocity=< 2,  0>
+position=<10, -3> velocity=<-1,  1>
+position=< 5, 11> velocity=< 1, -2>
+position=< 4,  7> velocity=< 0, -1>
+position=< 8, -2> velocity=< 0,  1>
+position=<15,  0> velocity=<-2,  0>
+position=< 1,  6> velocity=< 1,  0>
+position=< 8,  9> velocity=< 0, -1>
+position=< 3,  3> velocity=<-1,  1>
+position=< 0,  5> velocity=< 0, -1>
+position=<-2,  2> velocity=< 2,  0>
+position=< 5, -2> velocity=< 1,  2>
+position=< 1,  4> velocity=< 2,  1>
+position=<-2,  7> velocity=< 2, -2>
+position=< 3,  6> velocity=<-1, -1>
+position=< 5,  0> velocity=< 1,  0>
+position=<-6,  0> velocity=< 2,  0>
+position=< 5,  9> velocity=< 1, -2>
+position=<14,  7> velocity=<-2,  0>
+position=<-3,  6> velocity=< 2, -1>"
+}
+
+#[test]
+fn test_windows_input() {
+    use crate::input::{as_windows, normalize};
+    let input = normalize(&as_windows(test_input()), false);
+    assert_eq!("3", run(Two, &input).value);
+}
+
+#[test]
+fn test_visualize() {
+    assert_eq!(
+        "#...#..###\n#...#...#.\n#...#...#.\n#####...#.\n#...#...#.\n#...#...#.\n#...#...#.\n#...#..###\n",
+        visualize(test_input())
+    );
+}
@@ -1,37 +1,38 @@
-use super::{Part, Part::*};
+use super::{progress, Answer, Part, Part::*};
 use std::cmp;
 use std::fmt;
 
-pub fn run(part: Part, input: &str) {
+pub fn run(part: Part, input: &str) -> Answer {
     let grid_serial: usize = input.trim().parse().unwrap();
-    match part {
-        One => println!("{}", largest_power(grid_serial)),
-        Two => println!("{}", max_powers(grid_serial)),
-    }
+    let square = match part {
+        One => largest_power(grid_serial),
+        Two => max_powers(grid_serial),
+    };
+    Answer::new(square).detail("total power", square.value)
 }
 
 #[derive(Copy, Clone, PartialEq, Debug)]
-struct Answer {
+struct Square {
     x: usize,
     y: usize,
     size: usize,
     value: i32,
 }
 
-impl fmt::Display for Answer {
+impl fmt::Display for Square {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{},{},{}", self.x, self.y, self.size)
     }
 }
 
 // returns (x,y) of the top-left of the largest 3x3 grid
-fn largest_power(grid_serial: usize) -> Answer {
+fn largest_power(grid_serial: usize) -> Square {
     let grid = init_grid(grid_serial);
-    let mut max = Answer {
+    let mut max = Square {
         x: 0,
         y: 0,
         size: 3,
-        value: -6 * (3 * 3) as i32,
+        value: -6 * (3 * 3),
     };
     for x in 0..=(300 - 3) {
         for y in 0..=(300 - 3) {
@@ -51,10 +52,10 @@ fn largest_power(grid_serial: usize) -> Answer {
     max
 }
 
-fn max_powers(grid_serial: usize) -> Answer {
+fn max_powers(grid_serial: usize) -> Square {
     let grid = init_grid(grid_serial);
     let sums = summed_area(&grid);
-    let mut answer = Answer {
+    let mut answer = Square {
         x: 0,
         y: 0,
         size: 0,
@@ -62,6 +63,7 @@ fn max_powers(grid_serial: usize) -> Answer {
     };
 
     for x in 0..299 {
+        progress::report_of("row", x + 1, 299);
         for y in 0..299 {
             for d in 1..(300 - cmp::max(x, y) - 1) {
                 let total = sums[x + d][y + d] + sums[x][y] - sums[x + d][y] - sums[x][y + d];
@@ -77,7 +79,7 @@ fn max_powers(grid_serial: usize) -> Answer {
     answer
 }
 
-fn summed_area(grid: &Vec<Vec<i32>>) -> Vec<Vec<i32>> {
+fn summed_area(grid: &[Vec<i32>]) -> Vec<Vec<i32>> {
     let mut sums = vec![vec![0; 300]; 300];
     for x in 0..300 {
         for y in 0..300 {
@@ -97,9 +99,9 @@ fn summed_area(grid: &Vec<Vec<i32>>) -> Vec<Vec<i32>> {
 
 fn init_grid(grid_serial: usize) -> Vec<Vec<i32>> {
     let mut grid = vec![vec![0; 300]; 300];
-    for x in 0..300 {
-        for y in 0..300 {
-            grid[x][y] = power_level(x + 1, y + 1, grid_serial);
+    for (x, column) in grid.iter_mut().enumerate() {
+        for (y, cell) in column.iter_mut().enumerate() {
+            *cell = power_level(x + 1, y + 1, grid_serial);
         }
     }
     grid
@@ -122,7 +124,7 @@ fn test_power_level() {
 #[test]
 fn test_largest_power() {
     assert_eq!(
-        Answer {
+        Square {
             x: 33,
             y: 45,
             size: 3,
@@ -131,7 +133,7 @@ fn test_largest_power() {
         largest_power(18)
     );
     assert_eq!(
-        Answer {
+        Square {
             x: 21,
             y: 61,
             size: 3,
@@ -145,7 +147,7 @@ fn test_largest_power() {
 #[test]
 fn test_max_powers() {
     assert_eq!(
-        Answer {
+        Square {
             x: 90,
             y: 269,
             size: 16,
@@ -154,7 +156,7 @@ fn test_max_powers() {
         max_powers(18)
     );
     assert_eq!(
-        Answer {
+        Square {
             x: 232,
             y: 251,
             size: 12,
@@ -163,3 +165,10 @@ fn test_max_powers() {
         max_powers(42)
     );
 }
+
+#[test]
+fn test_windows_input() {
+    use crate::input::{as_windows, normalize};
+    let input = normalize(&as_windows("18"), false);
+    assert_eq!("33,45,3", run(One, &input).value);
+}
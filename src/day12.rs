@@ -1,12 +1,12 @@
-use super::{Part, Part::*};
+use super::{Answer, Part, Part::*};
 use std::collections::VecDeque;
 use std::fmt;
 
-pub fn run(part: Part, input: &str) {
-    match part {
-        One => println!("{}", part1(input, 20)),
-        Two => println!("{}", part2(input, 50000000000)),
-    }
+pub fn run(part: Part, input: &str) -> Answer {
+    Answer::new(match part {
+        One => part1(input, 20),
+        Two => part2(input, 50000000000),
+    })
 }
 
 fn part1(input: &str, generations: i64) -> i64 {
@@ -53,6 +53,10 @@ fn part2(input: &str, generations: i64) -> i64 {
     }
 }
 
+pub fn parse(input: &str) {
+    std::hint::black_box(parse_input(input));
+}
+
 fn parse_input(input: &str) -> (u32, State) {
     let mut rules = 0;
     let mut state = State {
@@ -92,7 +96,7 @@ struct State {
 impl fmt::Debug for State {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut s = String::new();
-        for (_, &b) in self.plants.iter().enumerate() {
+        for &b in self.plants.iter() {
             if b {
                 s.push('#');
             } else {
@@ -158,7 +162,7 @@ impl State {
 }
 
 fn grow(rules: u32, s1: &State, s2: &mut State) {
-    s2.expand(&s1);
+    s2.expand(s1);
 
     let mut v = 0u32;
     for i in s2.start()..=s2.end() {
@@ -182,6 +186,8 @@ fn grow(rules: u32, s1: &State, s2: &mut State) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::input::{as_windows, normalize};
+    use crate::snapshot::assert_snapshot;
     use std::iter::FromIterator;
 
     fn test_input() -> &'static str {
@@ -205,6 +211,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_parse_input() {
         let (rules, state) = parse_input(test_input());
         assert_eq!(
@@ -266,4 +273,28 @@ mod tests {
     fn test_part1() {
         assert_eq!(325, part1(test_input(), 20));
     }
+
+    #[test]
+    fn test_windows_input() {
+        let input = normalize(&as_windows(test_input()), false);
+        assert_eq!("325", run(One, &input).value);
+    }
+
+    // every generation of the example, as <offset>[pots]
+    #[test]
+    fn test_snapshot() {
+        let (rules, mut state) = parse_input(test_input());
+        let mut next = State {
+            plants: VecDeque::new(),
+            offset: 0,
+        };
+        let mut output = format!(" 0: {:?}\n", state);
+        for generation in 1..=20 {
+            grow(rules, &state, &mut next);
+            std::mem::swap(&mut state, &mut next);
+            output.push_str(&format!("{:2}: {:?}\n", generation, state));
+        }
+        output.push_str(&format!("sum: {}\n", state.sum_pots()));
+        assert_snapshot("day12", &output);
+    }
 }
@@ -1,13 +1,14 @@
-use super::{Part, Part::*};
+use super::{Answer, Part, Part::*};
 use std::fmt;
 use std::fmt::Write;
 
-pub fn run(part: Part, input: &str) {
+pub fn run(part: Part, input: &str) -> Answer {
     let mut mine = parse_input(input);
-    match part {
-        One => println!("{:?}", mine.first_crash()),
-        Two => println!("{:?}", mine.last_cart()),
-    }
+    let position = match part {
+        One => mine.first_crash(),
+        Two => mine.last_cart(),
+    };
+    Answer::new(format!("{:?}", position))
 }
 
 #[derive(Clone)]
@@ -22,7 +23,7 @@ enum Cell {
 
 impl Cell {
     fn to_char(&self) -> char {
-        match &self {
+        match self {
             Cell::Empty => ' ',
             Cell::Corner1 => '/',
             Cell::Corner2 => '\\',
@@ -52,7 +53,7 @@ impl Direction {
         }
     }
 
-    fn to_char(&self) -> char {
+    fn to_char(self) -> char {
         match self {
             Direction::North => '^',
             Direction::East => '>',
@@ -110,7 +111,7 @@ impl Cart {
         }
     }
 
-    fn move_turn(&mut self, map: &Vec<Vec<Cell>>) {
+    fn move_turn(&mut self, map: &[Vec<Cell>]) {
         match self.facing {
             Direction::North => self.y -= 1,
             Direction::East => self.x += 1,
@@ -137,6 +138,23 @@ impl Cart {
     }
 }
 
+// the map at the first crash, marked with an X
+pub fn visualize(input: &str) -> String {
+    let mut mine = parse_input(input);
+    let crash = mine.first_crash();
+    format!("{:?}", mine)
+        .lines()
+        .enumerate()
+        .map(|(y, line)| {
+            let mut line: Vec<char> = line.chars().collect();
+            if y == crash.y {
+                line[crash.x] = 'X';
+            }
+            line.into_iter().collect::<String>() + "\n"
+        })
+        .collect()
+}
+
 struct Mine {
     map: Vec<Vec<Cell>>,
     carts: Vec<Cart>, // kept in order
@@ -163,26 +181,51 @@ impl fmt::Debug for Mine {
 impl Mine {
     fn first_crash(&mut self) -> Position {
         loop {
-            for cart_id in 0..self.carts.len() {
-                self.carts[cart_id].move_turn(&self.map);
-
-                // has it crashed?
-                if self.detect_crash(cart_id) {
-                    return Position {
-                        x: self.carts[cart_id].x,
-                        y: self.carts[cart_id].y,
-                    };
-                }
+            if let Some(crash) = self.tick().into_iter().next() {
+                return crash;
             }
-            self.carts.sort_by_key(|c| (c.y, c.x));
         }
     }
 
+    fn last_cart(&mut self) -> Position {
+        while self.carts.len() > 1 {
+            self.tick();
+        }
+        Position {
+            x: self.carts[0].x,
+            y: self.carts[0].y,
+        }
+    }
+
+    // move every cart once, in order.  carts that crash are removed, and the
+    // positions of the crashes are returned.
+    fn tick(&mut self) -> Vec<Position> {
+        let mut crashes = vec![];
+        for cart_id in 0..self.carts.len() {
+            if self.carts[cart_id].destroyed {
+                continue;
+            }
+            self.carts[cart_id].move_turn(&self.map);
+
+            // has it crashed?
+            if self.detect_crash(cart_id) {
+                self.carts[cart_id].destroyed = true;
+                crashes.push(Position {
+                    x: self.carts[cart_id].x,
+                    y: self.carts[cart_id].y,
+                });
+            }
+        }
+        self.carts.retain(|cart| !cart.destroyed);
+        self.carts.sort_by_key(|c| (c.y, c.x));
+        crashes
+    }
+
     fn detect_crash(&mut self, this_id: usize) -> bool {
         let this_x = self.carts[this_id].x;
         let this_y = self.carts[this_id].y;
         for (other_id, other) in self.carts.iter_mut().enumerate() {
-            if other_id == this_id {
+            if other_id == this_id || other.destroyed {
                 continue;
             }
             if this_x == other.x && this_y == other.y {
@@ -192,26 +235,10 @@ impl Mine {
         }
         false
     }
+}
 
-    fn last_cart(&mut self) -> Position {
-        loop {
-            for cart_id in 0..self.carts.len() {
-                self.carts[cart_id].move_turn(&self.map);
-
-                if self.detect_crash(cart_id) {
-                    self.carts[cart_id].destroyed = true;
-                }
-            }
-            self.carts.retain(|cart| !cart.destroyed);
-            if self.carts.len() == 1 {
-                return Position {
-                    x: self.carts[0].x,
-                    y: self.carts[0].y,
-                };
-            }
-            self.carts.sort_by_key(|c| (c.y, c.x));
-        }
-    }
+pub fn parse(input: &str) {
+    std::hint::black_box(parse_input(input));
 }
 
 fn parse_input(input: &str) -> Mine {
@@ -235,8 +262,8 @@ fn parse_input(input: &str) -> Mine {
                     mine.map[y][x] = Cell::EW;
                 }
                 mine.carts.push(Cart {
-                    x: x,
-                    y: y,
+                    x,
+                    y,
                     facing: Direction::from_char(c).unwrap(),
                     next_turn: Turn::Left,
                     destroyed: false,
@@ -256,6 +283,8 @@ fn parse_input(input: &str) -> Mine {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::input::{as_windows, normalize};
+    use crate::snapshot::assert_snapshot;
 
     fn test_input() -> &'static str {
         "/->-\\        \n|   |  /----\\\n| /-+--+-\\  |\n| | |  | v  |\n\\-+-/  \\-+--/\n  \\------/   \n"
@@ -271,6 +300,13 @@ mod tests {
         assert_eq!(format!("{:?}", parse_input(test_input2())), test_input2());
     }
 
+    #[test]
+    fn test_parse_input_crlf() {
+        // the spaces at the end of rows are part of the map
+        let input = normalize(&as_windows(test_input()), false);
+        assert_eq!(format!("{:?}", parse_input(&input)), test_input());
+    }
+
     #[test]
     fn test_first_crash() {
         let mut mine = parse_input(test_input());
@@ -282,4 +318,38 @@ mod tests {
         let mut mine = parse_input(test_input2());
         assert_eq!(Position { x: 6, y: 4 }, mine.last_cart());
     }
+
+    #[test]
+    fn test_visualize() {
+        assert_eq!(
+            "/---\\        \n|   |  /----\\\n| /-+--+-\\  |\n| | |  X |  |\n\\-+-/  \\-+--/\n  \\------/   \n",
+            visualize(test_input())
+        );
+    }
+
+    // the map after every tick, until the first crash or until there is
+    // only one cart left
+    fn render_ticks(input: &str, until_first_crash: bool) -> String {
+        let mut mine = parse_input(input);
+        let mut output = format!("Initial state:\n{:?}", mine);
+        let mut ticks = 0;
+        while mine.carts.len() > 1 {
+            let crashes = mine.tick();
+            ticks += 1;
+            output.push_str(&format!("\nAfter {} ticks:\n{:?}", ticks, mine));
+            if !crashes.is_empty() {
+                output.push_str(&format!("Crashes: {:?}\n", crashes));
+                if until_first_crash {
+                    break;
+                }
+            }
+        }
+        output
+    }
+
+    #[test]
+    fn test_snapshot() {
+        assert_snapshot("day13-first-crash", &render_ticks(test_input(), true));
+        assert_snapshot("day13-last-cart", &render_ticks(test_input2(), false));
+    }
 }
@@ -1,12 +1,16 @@
-use super::{Part, Part::*};
+use super::{progress, Answer, Part, Part::*};
 
-pub fn run(part: Part, input: &str) {
+pub fn run(part: Part, input: &str) -> Answer {
     match part {
-        One => println!("{}", part1(parse_input(input))),
-        Two => println!("{}", part2(input.trim())),
+        One => Answer::new(part1(parse_input(input))),
+        Two => Answer::new(part2(input.trim())),
     }
 }
 
+pub fn parse(input: &str) {
+    std::hint::black_box(parse_input(input));
+}
+
 fn parse_input(input: &str) -> usize {
     input.trim().parse().unwrap()
 }
@@ -40,7 +44,12 @@ fn part2(pattern: &str) -> usize {
     let pattern: Vec<u8> = pattern.chars().map(|c| c as u8 - 48).collect();
     let mut recipes = starting_recipes();
     let mut elves: Vec<usize> = vec![0, 1];
+    let mut next_report = 0;
     loop {
+        if recipes.len() >= next_report {
+            progress::report("recipes", recipes.len());
+            next_report += 1 << 20;
+        }
         let new = recipes[elves[0]] + recipes[elves[1]];
         if new >= 10 {
             recipes.push(new / 10);
@@ -61,6 +70,7 @@ fn part2(pattern: &str) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::input::{as_windows, normalize};
 
     #[test]
     fn test_part1() {
@@ -77,4 +87,13 @@ mod tests {
         assert_eq!(18, part2("92510"));
         assert_eq!(2018, part2("59414"));
     }
+
+    #[test]
+    fn test_windows_input() {
+        assert_eq!(
+            "5158916779",
+            run(One, &normalize(&as_windows("9"), false)).value
+        );
+        assert_eq!("9", run(Two, &normalize(&as_windows("51589"), false)).value);
+    }
 }
@@ -1,11 +1,11 @@
 use self::Dir::*;
-use super::{Part, Part::*};
+use super::{search, Answer, Part, Part::*};
 use std::collections::HashSet;
 use std::fmt;
 use std::fmt::Write;
 use std::slice::Iter;
 
-pub fn run(part: Part, input: &str) {
+pub fn run(part: Part, input: &str) -> Answer {
     let game = match part {
         One => {
             let mut game = Game::new(input);
@@ -14,7 +14,22 @@ pub fn run(part: Part, input: &str) {
         }
         Two => Game::help_elves(input),
     };
-    println!("{}", game.rounds * game.total_hp());
+    Answer::new(game.rounds * game.total_hp())
+        .detail("winner", format!("{:?}", game.winner.unwrap()))
+        .detail("rounds", game.rounds)
+        .detail("total hp", game.total_hp())
+        .detail("elf power", game.elf_power)
+}
+
+pub fn parse(input: &str) {
+    std::hint::black_box(Game::new(input));
+}
+
+// the battlefield when part 1's combat ends
+pub fn visualize(input: &str) -> String {
+    let mut game = Game::new(input);
+    game.simulate(None);
+    format!("After {} full rounds\n{:?}", game.rounds, game)
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -24,7 +39,7 @@ enum Team {
 }
 
 impl Team {
-    fn to_char(&self) -> char {
+    fn to_char(self) -> char {
         match &self {
             Team::Elves => 'E',
             Team::Goblins => 'G',
@@ -128,7 +143,7 @@ enum Dir {
 impl Dir {
     fn each() -> Iter<'static, Dir> {
         static DIRS: [Dir; 4] = [North, West, East, South];
-        DIRS.into_iter()
+        DIRS.iter()
     }
 }
 
@@ -172,47 +187,52 @@ impl Game {
 
     // part 2, brute-force the minimal attack strength elves would need to win
     fn help_elves(input: &str) -> Game {
-        let mut elf_power = 3;
-        loop {
-            elf_power += 1;
+        let (_, game) = search::smallest_from(4, "elf power", |elf_power| {
             let mut game = Game::new(input);
-            game.set_elf_power(elf_power);
+            game.set_elf_power(elf_power as u8);
             game.simulate(Some(Team::Elves));
             if game.winner == Some(Team::Elves) {
-                return game;
+                Some(game)
+            } else {
+                None
             }
-        }
+        });
+        game
     }
 
     fn simulate(&mut self, require_total_victory: Option<Team>) {
-        loop {
-            if self.debug {
-                print!("After {} round(s)\n{:?}", self.rounds, self);
-            }
-            for u in 0..self.units.len() {
-                if self.is_victory(&self.units[u].team) {
-                    self.winner = Some(self.units[u].team);
-                    if self.debug {
-                        println!("Unit {} sees the battlefield is clear. Victory!", u);
-                    }
-                    return;
+        while self.round(require_total_victory) {}
+    }
+
+    // play one round, returns false once combat is over
+    fn round(&mut self, require_total_victory: Option<Team>) -> bool {
+        if self.debug {
+            print!("After {} round(s)\n{:?}", self.rounds, self);
+        }
+        for u in 0..self.units.len() {
+            if self.is_victory(&self.units[u].team) {
+                self.winner = Some(self.units[u].team);
+                if self.debug {
+                    println!("Unit {} sees the battlefield is clear. Victory!", u);
                 }
-                self.move_unit(u);
-                self.attack(u);
+                return false;
             }
-            if let Some(team) = require_total_victory {
-                if self
-                    .units
-                    .iter()
-                    .any(|unit| unit.team == team && !unit.is_alive())
-                {
-                    return;
-                }
+            self.move_unit(u);
+            self.attack(u);
+        }
+        if let Some(team) = require_total_victory {
+            if self
+                .units
+                .iter()
+                .any(|unit| unit.team == team && !unit.is_alive())
+            {
+                return false;
             }
-            self.units.retain(|unit| unit.is_alive());
-            self.sort_units();
-            self.rounds += 1;
         }
+        self.units.retain(|unit| unit.is_alive());
+        self.sort_units();
+        self.rounds += 1;
+        true
     }
 
     // returns false if there are no enemies left anywhere
@@ -425,7 +445,7 @@ impl fmt::Debug for Game {
                     .unwrap();
                 }
             }
-            if annot.len() > 0 {
+            if !annot.is_empty() {
                 f.write_str(&annot).unwrap();
                 annot.clear();
             }
@@ -438,6 +458,8 @@ impl fmt::Debug for Game {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::input::{as_windows, normalize};
+    use crate::snapshot::assert_snapshot;
 
     fn test_input() -> &'static str {
         "\
@@ -644,6 +666,16 @@ mod tests {
         game.sort_units();
     }
 
+    #[test]
+    fn test_new_crlf() {
+        let game = Game::new(&normalize(&as_windows(test_input4()), false));
+        assert_eq!(
+            format!("{:?}", Game::new(test_input4())),
+            format!("{:?}", game)
+        );
+        assert!(game.map.iter().all(|row| row.len() == 7));
+    }
+
     #[test]
     fn test_combat() {
         for &(input, expected_rounds, expected_total_hp) in [
@@ -680,4 +712,30 @@ mod tests {
             assert_eq!(expected_total_hp, game.total_hp());
         }
     }
+
+    // the map after every round of the first combat example, and of the
+    // same combat once the elves are strong enough to win without losses
+    #[test]
+    fn test_snapshot() {
+        for &(name, elf_power) in &[("day15-combat", 3), ("day15-help-elves", 15)] {
+            let mut game = Game::new(test_input4());
+            game.set_elf_power(elf_power);
+            let mut output = format!("Initially:\n{:?}", game);
+            let require_total_victory = if elf_power > 3 {
+                Some(Team::Elves)
+            } else {
+                None
+            };
+            while game.round(require_total_victory) {
+                output.push_str(&format!("\nAfter {} rounds:\n{:?}", game.rounds, game));
+            }
+            output.push_str(&format!(
+                "\nCombat ends after {} full rounds\n{:?} win with {} total hit points left\n",
+                game.rounds,
+                game.winner.unwrap(),
+                game.total_hp()
+            ));
+            assert_snapshot(name, &output);
+        }
+    }
 }
@@ -1,28 +1,33 @@
 use self::Op::*;
-use super::{Part, Part::*};
+use super::{Answer, Part, Part::*};
 use std::collections::HashSet;
 use std::slice::Iter;
 
-pub fn run(part: Part, input: &str) {
+pub fn run(part: Part, input: &str) -> Answer {
     let (samples, program) = parse_input(input);
     match part {
-        One => println!(
-            "{}",
+        One => Answer::new(
             samples
                 .iter()
                 .filter(|sample| sample.probe().len() >= 3)
-                .count()
-        ),
+                .count(),
+        )
+        .detail("samples", samples.len()),
         Two => {
-            let mut vm = Device::new();
-            vm.run_program(&reverse_engineer(&samples), &program);
-            println!("{}", vm.reg[0]);
+            let map = reverse_engineer(&samples);
+            let mut vm = Device::<4>::new();
+            vm.run_program(&map, &program);
+            let mut answer = Answer::new(vm.reg[0]);
+            for (opcode, op) in map.iter().enumerate() {
+                answer = answer.detail(format!("opcode {}", opcode), format!("{:?}", op));
+            }
+            answer
         }
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Op {
+pub enum Op {
     Addr,
     Addi,
     Mulr,
@@ -47,30 +52,40 @@ impl Op {
             Addr, Addi, Mulr, Muli, Banr, Bani, Borr, Bori, Setr, Seti, Gtir, Gtri, Gtrr, Eqir,
             Eqri, Eqrr,
         ];
-        OPCODES.into_iter()
+        OPCODES.iter()
+    }
+
+    // "addr", "seti" etc. as written in day 19 and 21 programs
+    pub fn from_mnemonic(s: &str) -> Option<Op> {
+        Op::each()
+            .find(|op| format!("{:?}", op).eq_ignore_ascii_case(s))
+            .copied()
     }
 }
 
+// Day 16's device has four registers, day 19's has six.
 #[derive(Debug, Clone, PartialEq)]
-struct Device {
-    reg: [usize; 4],
+pub struct Device<const N: usize> {
+    pub reg: [usize; N],
 }
 
-impl Device {
-    fn new() -> Device {
-        Device { reg: [0, 0, 0, 0] }
+impl<const N: usize> Device<N> {
+    pub fn new() -> Device<N> {
+        Device { reg: [0; N] }
     }
 
-    fn run_program(&mut self, map: &Vec<Op>, program: &Vec<Inst>) {
+    fn run_program(&mut self, map: &[Op], program: &[Inst]) {
         for inst in program.iter() {
             self.exec(map[inst.opcode], inst.a, inst.b, inst.c);
         }
     }
 
     #[rustfmt::skip]
-    fn exec(&mut self, op: Op, av: usize, bv: usize, c: usize) {
-        let ar = self.reg[av];
-        let br = self.reg[bv];
+    pub fn exec(&mut self, op: Op, av: usize, bv: usize, c: usize) {
+        // an immediate can be bigger than the last register, in which case
+        // its register value is never used
+        let ar = self.reg.get(av).copied().unwrap_or(0);
+        let br = self.reg.get(bv).copied().unwrap_or(0);
         self.reg[c] = match op {
             Addr => ar + br,
             Addi => ar + bv,
@@ -94,9 +109,9 @@ impl Device {
 
 #[derive(Debug, Clone)]
 struct Sample {
-    before: Device,
+    before: Device<4>,
     instr: [usize; 4],
-    after: Device,
+    after: Device<4>,
 }
 
 impl Sample {
@@ -113,7 +128,7 @@ impl Sample {
         let mut results = HashSet::new();
         for op in Op::each() {
             let mut vm = self.before.clone();
-            vm.exec(*op, self.instr[1], self.instr[2], self.instr[3] as usize);
+            vm.exec(*op, self.instr[1], self.instr[2], self.instr[3]);
             if vm == self.after {
                 results.insert(*op);
             }
@@ -145,12 +160,10 @@ fn reverse_engineer(samples: &Vec<Sample>) -> Vec<Op> {
         for i in 0..maybe.len() {
             if maybe[i].len() == 1 && !elim[i] {
                 elim[i] = true;
-                let op = maybe[i].iter().nth(0).unwrap().clone();
-                for j in 0..maybe.len() {
-                    if i != j {
-                        if maybe[j].remove(&op) {
-                            done = false;
-                        }
+                let op = *maybe[i].iter().next().unwrap();
+                for (j, other) in maybe.iter_mut().enumerate() {
+                    if i != j && other.remove(&op) {
+                        done = false;
                     }
                 }
             }
@@ -160,7 +173,7 @@ fn reverse_engineer(samples: &Vec<Sample>) -> Vec<Op> {
     // each maybe is now a set of 1
     maybe
         .iter()
-        .map(|set| *set.iter().nth(0).unwrap())
+        .map(|set| *set.iter().next().unwrap())
         .collect()
 }
 
@@ -172,6 +185,10 @@ struct Inst {
     c: usize,
 }
 
+pub fn parse(input: &str) {
+    std::hint::black_box(parse_input(input));
+}
+
 fn parse_input(input: &str) -> (Vec<Sample>, Vec<Inst>) {
     let mut samples = vec![];
     let mut sample = Sample::new();
@@ -191,8 +208,8 @@ fn parse_input(input: &str) -> (Vec<Sample>, Vec<Inst>) {
             }
             samples.push(sample.clone());
             in_samples = false;
-        } else if line.len() > 0 {
-            let numbers = parse_numbers(&line, " ");
+        } else if !line.is_empty() {
+            let numbers = parse_numbers(line, " ");
             if in_samples {
                 for (i, n) in numbers.into_iter().enumerate() {
                     sample.instr[i] = n;
@@ -217,6 +234,7 @@ fn parse_numbers(input: &str, delim: &str) -> Vec<usize> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::input::{as_windows, normalize};
 
     fn test_input() -> &'static str {
         "Before: [3, 2, 1, 1]\n9 2 1 2\nAfter:  [3, 2, 2, 1]\n\n"
@@ -237,4 +255,17 @@ mod tests {
         expected.insert(Seti);
         assert_eq!(expected, samples[0].probe());
     }
+
+    #[test]
+    fn test_from_mnemonic() {
+        assert_eq!(Some(Addr), Op::from_mnemonic("addr"));
+        assert_eq!(Some(Eqrr), Op::from_mnemonic("eqrr"));
+        assert_eq!(None, Op::from_mnemonic("nope"));
+    }
+
+    #[test]
+    fn test_windows_input() {
+        let input = normalize(&as_windows(test_input()), false);
+        assert_eq!("1", run(One, &input).value);
+    }
 }
@@ -0,0 +1,286 @@
+use super::{config, Answer, Part, Part::*};
+use image::ImageBuffer;
+use regex::Regex;
+use std::fmt;
+use std::fmt::Write;
+use std::path::Path;
+
+pub fn run(part: Part, input: &str) -> Answer {
+    let mut ground = parse_input(input);
+    ground.flow();
+    let answer = match part {
+        One => Answer::new(ground.count(|cell| cell == Flowing || cell == Still)),
+        Two => Answer::new(ground.count(|cell| cell == Still)),
+    };
+    if config::param(17, "image", 0) != 0 {
+        let path = config::get().output_path("day17.png");
+        ground.to_image(&path);
+        return answer.detail("image", path.display());
+    }
+    answer
+}
+
+// the ground slice once the water has stopped spreading
+pub fn visualize(input: &str) -> String {
+    let mut ground = parse_input(input);
+    ground.flow();
+    format!("{:?}", ground)
+}
+
+const SPRING: usize = 500;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Cell {
+    Sand,
+    Clay,
+    Flowing,
+    Still,
+}
+
+use self::Cell::*;
+
+impl Cell {
+    fn to_char(self) -> char {
+        match self {
+            Sand => '.',
+            Clay => '#',
+            Flowing => '|',
+            Still => '~',
+        }
+    }
+
+    // whether water on top of this spreads sideways rather than falling
+    fn holds_water(self) -> bool {
+        self == Clay || self == Still
+    }
+}
+
+struct Ground {
+    cells: Vec<Vec<Cell>>, // [y][x - left]
+    left: usize,
+    min_y: usize, // first row with clay; rows above it aren't counted
+}
+
+impl fmt::Debug for Ground {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (y, row) in self.cells.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if y == 0 && x + self.left == SPRING {
+                    f.write_char('+')?;
+                } else {
+                    f.write_char(cell.to_char())?;
+                }
+            }
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+impl Ground {
+    fn get(&self, x: usize, y: usize) -> Cell {
+        self.cells[y][x - self.left]
+    }
+
+    fn set(&mut self, x: usize, y: usize, cell: Cell) {
+        self.cells[y][x - self.left] = cell;
+    }
+
+    fn max_y(&self) -> usize {
+        self.cells.len() - 1
+    }
+
+    fn count<F: Fn(Cell) -> bool>(&self, f: F) -> usize {
+        self.cells[self.min_y..]
+            .iter()
+            .map(|row| row.iter().filter(|&&cell| f(cell)).count())
+            .sum()
+    }
+
+    fn flow(&mut self) {
+        self.set(SPRING, 0, Flowing);
+        self.fall(SPRING, 0);
+    }
+
+    // water has reached (x, top): let it fall, then fill whatever it lands
+    // in one level at a time.  Levels that overflow fall again at their
+    // edges.
+    fn fall(&mut self, x: usize, top: usize) {
+        let mut y = top;
+        while y < self.max_y() && self.get(x, y + 1) == Sand {
+            y += 1;
+            self.set(x, y, Flowing);
+        }
+        if y == self.max_y() || !self.get(x, y + 1).holds_water() {
+            return;
+        }
+        while y >= top {
+            let (left, left_wall) = self.spread(x, y, -1);
+            let (right, right_wall) = self.spread(x, y, 1);
+            if left_wall && right_wall {
+                for x in left..=right {
+                    self.set(x, y, Still);
+                }
+                if y == top {
+                    // the level above belongs to whoever poured us
+                    return;
+                }
+                y -= 1;
+                continue;
+            }
+            for x in left..=right {
+                self.set(x, y, Flowing);
+            }
+            // an edge can fill a basin of its own and end up with something
+            // to stand on, in which case this level spreads further
+            let mut supported = false;
+            for &(edge, wall) in &[(left, left_wall), (right, right_wall)] {
+                if !wall {
+                    self.fall(edge, y);
+                    supported |= self.get(edge, y + 1).holds_water();
+                }
+            }
+            if !supported {
+                return;
+            }
+        }
+    }
+
+    // how far water at (x, y) spreads in a direction: to the last cell
+    // before a wall, or to the first one it can fall from
+    fn spread(&self, x: usize, y: usize, dx: isize) -> (usize, bool) {
+        let mut x = x;
+        loop {
+            if !self.get(x, y + 1).holds_water() {
+                return (x, false);
+            }
+            let next = (x as isize + dx) as usize;
+            if self.get(next, y) == Clay {
+                return (x, true);
+            }
+            x = next;
+        }
+    }
+
+    fn to_image(&self, path: &Path) {
+        let img = ImageBuffer::from_fn(
+            self.cells[0].len() as u32,
+            self.cells.len() as u32,
+            |x, y| {
+                image::Rgb(match self.cells[y as usize][x as usize] {
+                    Sand => [230u8, 210, 160],
+                    Clay => [120, 70, 30],
+                    Flowing => [120, 180, 255],
+                    Still => [20, 60, 200],
+                })
+            },
+        );
+        img.save(path).unwrap();
+    }
+}
+
+pub fn parse(input: &str) {
+    std::hint::black_box(parse_input(input));
+}
+
+fn parse_input(input: &str) -> Ground {
+    let re = Regex::new(r"^([xy])=(\d+), ([xy])=(\d+)\.\.(\d+)$").unwrap();
+    let mut veins = vec![];
+    for line in input.lines() {
+        match re.captures(line) {
+            Some(cap) => {
+                let a: usize = cap[2].parse().unwrap();
+                let b1: usize = cap[4].parse().unwrap();
+                let b2: usize = cap[5].parse().unwrap();
+                // (x range, y range)
+                veins.push(match (&cap[1], &cap[3]) {
+                    ("x", "y") => ((a, a), (b1, b2)),
+                    ("y", "x") => ((b1, b2), (a, a)),
+                    _ => panic!("parse error: {}", line),
+                });
+            }
+            None => panic!("parse error: {}", line),
+        }
+    }
+
+    // one column of sand either side for water to fall down
+    let left = veins.iter().map(|v| (v.0).0).min().unwrap().min(SPRING) - 1;
+    let right = veins.iter().map(|v| (v.0).1).max().unwrap().max(SPRING) + 1;
+    let min_y = veins.iter().map(|v| (v.1).0).min().unwrap();
+    let max_y = veins.iter().map(|v| (v.1).1).max().unwrap();
+    let mut ground = Ground {
+        cells: vec![vec![Sand; right - left + 1]; max_y + 1],
+        left,
+        min_y,
+    };
+    for ((x1, x2), (y1, y2)) in veins {
+        for x in x1..=x2 {
+            for y in y1..=y2 {
+                ground.set(x, y, Clay);
+            }
+        }
+    }
+    ground
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{as_windows, normalize};
+
+    fn test_input() -> &'static str {
+        "x=495, y=2..7
+y=7, x=495..501
+x=501, y=3..7
+x=498, y=2..4
+x=506, y=1..2
+x=498, y=10..13
+x=504, y=10..13
+y=13, x=498..504
+"
+    }
+
+    #[test]
+    fn test_run() {
+        assert_eq!("57", run(One, test_input()).value);
+        assert_eq!("29", run(Two, test_input()).value);
+    }
+
+    #[test]
+    fn test_visualize() {
+        assert_eq!(
+            "......+.......
+......|.....#.
+.#..#||||...#.
+.#..#~~#|.....
+.#..#~~#|.....
+.#~~~~~#|.....
+.#~~~~~#|.....
+.#######|.....
+........|.....
+...|||||||||..
+...|#~~~~~#|..
+...|#~~~~~#|..
+...|#~~~~~#|..
+...|#######|..
+",
+            visualize(test_input())
+        );
+    }
+
+    // a cup inside a basin: water falls past the cup, fills the basin's
+    // floor, then has to fill the cup before the basin can fill around it
+    #[test]
+    fn test_nested_basins() {
+        let input = "x=496, y=3..8\nx=506, y=3..8\ny=8, x=496..506\nx=500, y=5..6\nx=502, y=5..6\ny=6, x=500..502\n";
+        assert_eq!("52", run(One, input).value);
+        assert_eq!("40", run(Two, input).value);
+    }
+
+    #[test]
+    fn test_windows_input() {
+        let input = normalize(&as_windows(test_input()), false);
+        assert_eq!("57", run(One, &input).value);
+        assert_eq!("29", run(Two, &input).value);
+    }
+}
@@ -0,0 +1,236 @@
+use super::{Answer, Part, Part::*};
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Write;
+
+pub fn run(part: Part, input: &str) -> Answer {
+    let area = parse_input(input);
+    match part {
+        One => Answer::new(area.after(10).resource_value()),
+        Two => {
+            let (area, cycle) = area.after_long(1_000_000_000);
+            Answer::new(area.resource_value())
+                .detail("cycle starts", cycle.start)
+                .detail("cycle length", cycle.length)
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Acre {
+    Open,
+    Trees,
+    Lumberyard,
+}
+
+use self::Acre::*;
+
+impl Acre {
+    fn to_char(self) -> char {
+        match self {
+            Open => '.',
+            Trees => '|',
+            Lumberyard => '#',
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct Area {
+    acres: Vec<Vec<Acre>>,
+}
+
+#[derive(Debug, PartialEq)]
+struct Cycle {
+    start: usize,
+    length: usize,
+}
+
+impl fmt::Debug for Area {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.acres.iter() {
+            for acre in row.iter() {
+                f.write_char(acre.to_char())?;
+            }
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+impl Area {
+    fn count(&self, acre: Acre) -> usize {
+        self.acres
+            .iter()
+            .map(|row| row.iter().filter(|&&a| a == acre).count())
+            .sum()
+    }
+
+    fn resource_value(&self) -> usize {
+        self.count(Trees) * self.count(Lumberyard)
+    }
+
+    // how many of the (up to 8) acres around (x, y) are of each kind
+    fn neighbours(&self, x: usize, y: usize) -> (usize, usize) {
+        let mut trees = 0;
+        let mut lumberyards = 0;
+        for ny in y.saturating_sub(1)..=(y + 1).min(self.acres.len() - 1) {
+            for nx in x.saturating_sub(1)..=(x + 1).min(self.acres[ny].len() - 1) {
+                if (nx, ny) == (x, y) {
+                    continue;
+                }
+                match self.acres[ny][nx] {
+                    Trees => trees += 1,
+                    Lumberyard => lumberyards += 1,
+                    Open => (),
+                }
+            }
+        }
+        (trees, lumberyards)
+    }
+
+    fn step(&self) -> Area {
+        let acres = self
+            .acres
+            .iter()
+            .enumerate()
+            .map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .map(|(x, &acre)| {
+                        let (trees, lumberyards) = self.neighbours(x, y);
+                        match acre {
+                            Open if trees >= 3 => Trees,
+                            Trees if lumberyards >= 3 => Lumberyard,
+                            Lumberyard if trees == 0 || lumberyards == 0 => Open,
+                            _ => acre,
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        Area { acres }
+    }
+
+    fn after(&self, minutes: usize) -> Area {
+        let mut area = self.clone();
+        for _ in 0..minutes {
+            area = area.step();
+        }
+        area
+    }
+
+    // Every area seen is remembered with the minute it was seen at.  The
+    // first repeat gives the cycle, and the state at `minutes` is the one
+    // at the same point in an earlier lap of it.
+    fn after_long(&self, minutes: usize) -> (Area, Cycle) {
+        let mut seen: HashMap<Area, usize> = HashMap::new();
+        let mut history = vec![];
+        let mut area = self.clone();
+        for minute in 0..minutes {
+            if let Some(&start) = seen.get(&area) {
+                let cycle = Cycle {
+                    start,
+                    length: minute - start,
+                };
+                let index = start + (minutes - start) % cycle.length;
+                return (history.swap_remove(index), cycle);
+            }
+            seen.insert(area.clone(), minute);
+            let next = area.step();
+            history.push(area);
+            area = next;
+        }
+        // finished before anything repeated
+        (
+            area,
+            Cycle {
+                start: minutes,
+                length: 0,
+            },
+        )
+    }
+}
+
+pub fn parse(input: &str) {
+    std::hint::black_box(parse_input(input));
+}
+
+fn parse_input(input: &str) -> Area {
+    Area {
+        acres: input
+            .lines()
+            .map(|line| {
+                line.chars()
+                    .map(|c| match c {
+                        '.' => Open,
+                        '|' => Trees,
+                        '#' => Lumberyard,
+                        _ => panic!("parse error: {}", line),
+                    })
+                    .collect()
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{as_windows, normalize};
+
+    fn test_input() -> &'static str {
+        ".#.#...|#.
+.....#|##|
+.|..|...#.
+..|#.....#
+#.#|||#|#|
+...#.||...
+.|....|...
+||...#|.#|
+|.||||..|.
+...#.|..|.
+"
+    }
+
+    #[test]
+    fn test_step() {
+        let area = parse_input(test_input()).step();
+        assert_eq!(
+            ".......##.
+......|###
+.|..|...#.
+..|#||...#
+..##||.|#|
+...#||||..
+||...|||..
+|||||.||.|
+||||||||||
+....||..|.
+",
+            format!("{:?}", area)
+        );
+    }
+
+    #[test]
+    fn test_after() {
+        let area = parse_input(test_input()).after(10);
+        assert_eq!(37, area.count(Trees));
+        assert_eq!(31, area.count(Lumberyard));
+        assert_eq!(1147, area.resource_value());
+    }
+
+    #[test]
+    fn test_after_long() {
+        let area = parse_input(test_input());
+        for &minutes in &[5, 10, 100, 1000] {
+            assert_eq!(area.after(minutes), area.after_long(minutes).0);
+        }
+    }
+
+    #[test]
+    fn test_windows_input() {
+        let input = normalize(&as_windows(test_input()), false);
+        assert_eq!("1147", run(One, &input).value);
+    }
+}
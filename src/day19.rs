@@ -0,0 +1,271 @@
+use super::day16::{Device, Op, Op::*};
+use super::{progress, Answer, Part, Part::*};
+
+pub fn run(part: Part, input: &str) -> Answer {
+    let program = parse_input(input);
+    let mut machine = Machine::new(&program);
+    if part == Two {
+        machine.device.reg[0] = 1;
+    }
+    match machine.run_fast() {
+        Some(target) => Answer::new(machine.device.reg[0]).detail("divisors of", target),
+        None => Answer::new(machine.device.reg[0]),
+    }
+}
+
+// part 1 is small enough to run one instruction at a time
+pub fn run_naive(part: Part, input: &str) -> Answer {
+    let program = parse_input(input);
+    let mut machine = Machine::new(&program);
+    if part == Two {
+        machine.device.reg[0] = 1;
+    }
+    machine.run();
+    Answer::new(machine.device.reg[0])
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Inst {
+    pub op: Op,
+    pub a: usize,
+    pub b: usize,
+    pub c: usize,
+}
+
+pub struct Program {
+    pub ip_reg: usize,
+    pub insts: Vec<Inst>,
+}
+
+impl Program {
+    // The puzzle's programs jump from instruction 0 to a setup section that
+    // puts a number in a register and then jumps back to 1, where this loop
+    // adds every divisor of it to another register, one pair of factors at a
+    // time.  Returns the registers holding the number and the sum, if the
+    // program has the loop (whatever the registers are).
+    fn divisor_sum_loop(&self) -> Option<(usize, usize)> {
+        #[rustfmt::skip]
+        const LOOP: [Op; 16] = [
+            Seti, Seti, Mulr, Eqrr, Addr, Addi, Addr, Addi,
+            Gtrr, Addr, Seti, Addi, Gtrr, Addr, Seti, Mulr,
+        ];
+        let body = self.insts.get(1..=LOOP.len())?;
+        if body.iter().map(|inst| inst.op).ne(LOOP.iter().copied()) {
+            return None;
+        }
+        // eqrr compares the product of the factors with the number, and the
+        // first addr adds the factor to the sum
+        Some((body[3].b, body[6].c))
+    }
+}
+
+pub struct Machine<'a> {
+    pub program: &'a Program,
+    pub device: Device<6>,
+    pub ip: usize,
+}
+
+impl<'a> Machine<'a> {
+    pub fn new(program: &'a Program) -> Machine<'a> {
+        Machine {
+            program,
+            device: Device::new(),
+            ip: 0,
+        }
+    }
+
+    // the instruction about to be executed, if the program hasn't halted
+    pub fn next_inst(&self) -> Option<&'a Inst> {
+        self.program.insts.get(self.ip)
+    }
+
+    // execute one instruction, returns false if the program has halted
+    pub fn step(&mut self) -> bool {
+        let inst = match self.next_inst() {
+            Some(inst) => *inst,
+            None => return false,
+        };
+        let ip_reg = self.program.ip_reg;
+        self.device.reg[ip_reg] = self.ip;
+        self.device.exec(inst.op, inst.a, inst.b, inst.c);
+        self.ip = self.device.reg[ip_reg] + 1;
+        true
+    }
+
+    pub fn run(&mut self) {
+        let mut steps: u64 = 0;
+        while self.step() {
+            steps += 1;
+            if steps.is_multiple_of(1 << 24) {
+                progress::report("steps", steps);
+            }
+        }
+    }
+
+    // run the setup, then sum the divisors directly instead of looping over
+    // every pair of numbers below the target.  Returns the number whose
+    // divisors were summed, or None if the program wasn't recognised and had
+    // to be run the slow way.
+    fn run_fast(&mut self) -> Option<usize> {
+        let (target_reg, sum_reg) = match self.program.divisor_sum_loop() {
+            Some(regs) => regs,
+            None => {
+                self.run();
+                return None;
+            }
+        };
+        // the loop is entered with instruction 1
+        while !(self.ip == 1 && self.device.reg[target_reg] > 0) {
+            if !self.step() {
+                return None;
+            }
+        }
+        let target = self.device.reg[target_reg];
+        self.device.reg[sum_reg] += divisor_sum(target);
+        self.ip = self.program.insts.len();
+        Some(target)
+    }
+}
+
+fn divisor_sum(n: usize) -> usize {
+    let mut sum = 0;
+    let mut i = 1;
+    while i * i <= n {
+        if n.is_multiple_of(i) {
+            sum += i;
+            if i * i != n {
+                sum += n / i;
+            }
+        }
+        i += 1;
+    }
+    sum
+}
+
+pub fn parse(input: &str) {
+    std::hint::black_box(parse_input(input));
+}
+
+pub fn parse_input(input: &str) -> Program {
+    let mut ip_reg = None;
+    let mut insts = vec![];
+    for line in input.lines() {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            ["#ip", reg] => ip_reg = Some(reg.parse().unwrap()),
+            [op, a, b, c] => insts.push(Inst {
+                op: Op::from_mnemonic(op).unwrap_or_else(|| panic!("parse error: {}", line)),
+                a: a.parse().unwrap(),
+                b: b.parse().unwrap(),
+                c: c.parse().unwrap(),
+            }),
+            [] => (),
+            _ => panic!("parse error: {}", line),
+        }
+    }
+    Program {
+        ip_reg: ip_reg.expect("no #ip line"),
+        insts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{as_windows, normalize};
+
+    fn test_input() -> &'static str {
+        "#ip 0
+seti 5 0 1
+seti 6 0 2
+addi 0 1 0
+addr 1 2 3
+setr 1 0 0
+seti 8 0 4
+seti 9 0 5
+"
+    }
+
+    // the shape of the real inputs, summing the divisors of 10 (or 30 in
+    // part 2)
+    fn divisor_program() -> &'static str {
+        "#ip 3
+addi 3 16 3
+seti 1 8 1
+seti 1 3 2
+mulr 1 2 4
+eqrr 4 5 4
+addr 4 3 3
+addi 3 1 3
+addr 1 0 0
+addi 2 1 2
+gtrr 2 5 4
+addr 3 4 3
+seti 2 8 3
+addi 1 1 1
+gtrr 1 5 4
+addr 4 3 3
+seti 1 5 3
+mulr 3 3 3
+seti 10 0 5
+addr 3 0 3
+seti 0 0 3
+muli 5 3 5
+seti 0 0 0
+seti 0 0 3
+"
+    }
+
+    #[test]
+    fn test_parse_input() {
+        let program = parse_input(test_input());
+        assert_eq!(0, program.ip_reg);
+        assert_eq!(7, program.insts.len());
+        assert_eq!(
+            Inst {
+                op: Addi,
+                a: 0,
+                b: 1,
+                c: 0
+            },
+            program.insts[2]
+        );
+    }
+
+    #[test]
+    fn test_run() {
+        let program = parse_input(test_input());
+        let mut machine = Machine::new(&program);
+        machine.run();
+        assert_eq!([6, 5, 6, 0, 0, 9], machine.device.reg);
+        assert_eq!(None, program.divisor_sum_loop());
+    }
+
+    #[test]
+    fn test_divisor_sum() {
+        assert_eq!(1, divisor_sum(1));
+        assert_eq!(18, divisor_sum(10));
+        assert_eq!(31, divisor_sum(16));
+        assert_eq!(72, divisor_sum(30));
+    }
+
+    #[test]
+    fn test_run_fast() {
+        let program = parse_input(divisor_program());
+        assert_eq!(Some((5, 0)), program.divisor_sum_loop());
+        for &part in &[One, Two] {
+            assert_eq!(
+                run_naive(part, divisor_program()).value,
+                run(part, divisor_program()).value
+            );
+        }
+        assert_eq!("18", run(One, divisor_program()).value);
+        assert_eq!("72", run(Two, divisor_program()).value);
+    }
+
+    #[test]
+    fn test_windows_input() {
+        let input = normalize(&as_windows(test_input()), false);
+        assert_eq!("6", run(One, &input).value);
+    }
+}
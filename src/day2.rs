@@ -1,13 +1,53 @@
-use super::{Part, Part::*};
+use super::sets::DisjointSets;
+use super::{config, Answer, Part, Part::*};
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 
-pub fn run(part: Part, input: &str) {
+pub fn run(part: Part, input: &str) -> Answer {
     match part {
-        One => println!("{}", checksum(input.lines().collect())),
-        Two => println!("{}", common_letters(input.lines().collect())),
+        One => Answer::new(checksum(input.lines().collect())),
+        Two => {
+            let (common, pairs) = common_letters(input.lines().collect());
+            Answer::new(common).detail("matching pairs", pairs)
+        }
     }
 }
 
+// Part 2 as edit distance families: IDs within the configured distance of
+// each other (insertions and deletions too, so lengths can differ) are in the
+// same family, as are any chained through others.  The answer is what the
+// first family has in common, and --explain lists them all.
+pub fn run_families(part: Part, input: &str) -> Answer {
+    if let One = part {
+        unreachable!("families only solves part two");
+    }
+    let max = config::param_usize(2, "distance", 1);
+    let ids: Vec<Vec<char>> = input.lines().map(|id| id.chars().collect()).collect();
+    let families = families(&ids, max);
+    let mut answer = Answer::new(match families.first() {
+        Some(family) => family.common.iter().collect::<String>(),
+        None => String::new(),
+    })
+    .detail("max distance", max);
+    for (n, family) in families.iter().enumerate() {
+        let members: Vec<String> = family
+            .ids
+            .iter()
+            .map(|&i| ids[i].iter().collect())
+            .collect();
+        answer = answer.detail(
+            format!("family {}", n + 1),
+            format!(
+                "{} (common {:?})",
+                members.join(", "),
+                family.common.iter().collect::<String>()
+            ),
+        );
+    }
+    answer
+}
+
 fn checksum(input: Vec<&str>) -> i32 {
     let mut t2 = 0;
     let mut t3 = 0;
@@ -29,36 +69,248 @@ fn checksum(input: Vec<&str>) -> i32 {
     t2 * t3
 }
 
-fn common_letters(input: Vec<&str>) -> String {
-    for (i, id1) in input.iter().enumerate() {
-        for id2 in input.iter().skip(i + 1) {
-            match compare(id1, id2) {
-                Some(s) => return s,
-                None => (),
+// the letters the first pair of IDs differing in exactly one place have in
+// common, or however many places the config allows
+fn common_letters(input: Vec<&str>) -> (String, usize) {
+    let k = config::param_usize(2, "mismatches", 1);
+    let ids: Vec<Vec<char>> = input.iter().map(|id| id.chars().collect()).collect();
+    let pairs = near_duplicates(&ids, k);
+    let common = match pairs.first() {
+        Some(pair) => ids[pair.a]
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !pair.mismatches.contains(i))
+            .map(|(_, &c)| c)
+            .collect(),
+        None => String::new(),
+    };
+    (common, pairs.len())
+}
+
+#[derive(Debug, PartialEq)]
+struct Pair {
+    // indexes of the two IDs, a < b
+    a: usize,
+    b: usize,
+    // the positions where they differ
+    mismatches: Vec<usize>,
+}
+
+// Every pair of IDs of the same length that differ in at least one and at
+// most k positions, in order.  IDs that agree everywhere except a set of k
+// positions are the same once those positions are masked out, so for each
+// set of k positions the IDs are bucketed by a hash of what's left.  A pair
+// that differs in fewer than k places turns up under several sets, and is
+// only kept under the one made of its mismatches plus the first other
+// positions.  Identical IDs are bucketed once, as a pile of copies would
+// otherwise fill every bucket with pairs to compare.  There are C(len, k)
+// sets of positions, so this is for small k.
+fn near_duplicates(ids: &[Vec<char>], k: usize) -> Vec<Pair> {
+    // each distinct ID and the indexes it appears at
+    let mut copies: HashMap<&[char], Vec<usize>> = HashMap::new();
+    for (i, id) in ids.iter().enumerate() {
+        copies.entry(id).or_default().push(i);
+    }
+    let mut by_length: HashMap<usize, Vec<&[char]>> = HashMap::new();
+    for &id in copies.keys() {
+        by_length.entry(id.len()).or_default().push(id);
+    }
+    let mut pairs = vec![];
+    for (&len, group) in by_length.iter() {
+        let k = k.min(len);
+        let mut masked = Combinations::new(len, k);
+        while let Some(positions) = masked.next() {
+            let mut buckets: HashMap<u64, Vec<&[char]>> = HashMap::new();
+            for &id in group {
+                buckets
+                    .entry(unmasked_hash(id, positions))
+                    .or_default()
+                    .push(id);
+            }
+            for bucket in buckets.values().filter(|bucket| bucket.len() > 1) {
+                for (n, &id1) in bucket.iter().enumerate() {
+                    for &id2 in bucket.iter().skip(n + 1) {
+                        // the hash can collide, so the mismatches are checked
+                        let mismatches = mismatches(id1, id2);
+                        if mismatches.len() > k || canonical(&mismatches, len, k) != positions {
+                            continue;
+                        }
+                        for &a in copies[id1].iter() {
+                            for &b in copies[id2].iter() {
+                                pairs.push(Pair {
+                                    a: a.min(b),
+                                    b: a.max(b),
+                                    mismatches: mismatches.clone(),
+                                });
+                            }
+                        }
+                    }
+                }
             }
         }
     }
-    String::new()
+    pairs.sort_by_key(|pair| (pair.a, pair.b));
+    pairs
+}
+
+// a hash of the parts of the ID between the masked positions
+fn unmasked_hash(id: &[char], positions: &[usize]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    let mut start = 0;
+    for &p in positions {
+        id[start..p].hash(&mut hasher);
+        start = p + 1;
+    }
+    id[start..].hash(&mut hasher);
+    hasher.finish()
+}
+
+fn mismatches(a: &[char], b: &[char]) -> Vec<usize> {
+    (0..a.len()).filter(|&i| a[i] != b[i]).collect()
+}
+
+// the set of k positions a pair with these mismatches is counted under
+fn canonical(mismatches: &[usize], len: usize, k: usize) -> Vec<usize> {
+    let mut positions = mismatches.to_vec();
+    positions.extend(
+        (0..len)
+            .filter(|p| !mismatches.contains(p))
+            .take(k - mismatches.len()),
+    );
+    positions.sort_unstable();
+    positions
 }
 
-// Return common characters for 2 strings, as long as there is exactly 1 difference
-fn compare(a: &str, b: &str) -> Option<String> {
-    let mut result = String::new();
-    let mut mismatches = false;
-    for (c1, c2) in a.chars().zip(b.chars()) {
-        if c1 == c2 {
-            result.push(c1);
-        } else if mismatches {
+// the k-element subsets of 0..n in lexicographic order
+struct Combinations {
+    n: usize,
+    current: Vec<usize>,
+    started: bool,
+}
+
+impl Combinations {
+    fn new(n: usize, k: usize) -> Combinations {
+        Combinations {
+            n,
+            current: (0..k).collect(),
+            started: false,
+        }
+    }
+
+    fn next(&mut self) -> Option<&[usize]> {
+        if self.started {
+            // bump the last position that can still move right, and reset
+            // the ones after it to follow straight on
+            let k = self.current.len();
+            let i = (0..k).rev().find(|&i| self.current[i] < self.n - k + i)?;
+            self.current[i] += 1;
+            for j in i + 1..k {
+                self.current[j] = self.current[j - 1] + 1;
+            }
+        }
+        self.started = true;
+        Some(&self.current)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Family {
+    // indexes of the IDs, in order
+    ids: Vec<usize>,
+    // a subsequence of every ID in the family
+    common: Vec<char>,
+}
+
+// groups of two or more IDs linked by edit distances of at most max, in
+// order of their first ID
+fn families(ids: &[Vec<char>], max: usize) -> Vec<Family> {
+    let mut sets = DisjointSets::new(ids.len());
+    for (a, id1) in ids.iter().enumerate() {
+        for (b, id2) in ids.iter().enumerate().skip(a + 1) {
+            if levenshtein(id1, id2, max).is_some() {
+                sets.union(a, b);
+            }
+        }
+    }
+    let mut members: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..ids.len() {
+        members.entry(sets.find(i)).or_default().push(i);
+    }
+    let mut families: Vec<Family> = members
+        .into_values()
+        .filter(|members| members.len() > 1)
+        .map(|members| {
+            // the longest common subsequence of each ID with what the ones
+            // before had in common; not always the longest for the family,
+            // but always common to all of it
+            let common = members[1..]
+                .iter()
+                .fold(ids[members[0]].clone(), |common, &i| lcs(&common, &ids[i]));
+            Family {
+                ids: members,
+                common,
+            }
+        })
+        .collect();
+    families.sort_by_key(|family| family.ids[0]);
+    families
+}
+
+// The fewest single character insertions, deletions and substitutions that
+// turn a into b, or None if that's more than max.  Only the cells within max
+// of the diagonal can be within max, so the rest are never filled in.
+fn levenshtein(a: &[char], b: &[char], max: usize) -> Option<usize> {
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+    let beyond = max + 1;
+    let mut prev: Vec<usize> = (0..=b.len()).map(|j| j.min(beyond)).collect();
+    let mut row = vec![beyond; b.len() + 1];
+    for i in 1..=a.len() {
+        let from = i.saturating_sub(max).max(1);
+        let to = (i + max).min(b.len());
+        row.iter_mut().for_each(|cell| *cell = beyond);
+        row[0] = i.min(beyond);
+        for j in from..=to {
+            let substitute = prev[j - 1] + (a[i - 1] != b[j - 1]) as usize;
+            row[j] = substitute.min(prev[j] + 1).min(row[j - 1] + 1).min(beyond);
+        }
+        // every way on from here is already too far
+        if row.iter().all(|&cell| cell > max) {
             return None;
-        } else {
-            mismatches = true;
+        }
+        std::mem::swap(&mut prev, &mut row);
+    }
+    Some(prev[b.len()]).filter(|&d| d <= max)
+}
+
+// a longest common subsequence of a and b
+fn lcs(a: &[char], b: &[char]) -> Vec<char> {
+    // lengths[i][j] is the length of the LCS of a[i..] and b[j..]
+    let mut lengths = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lengths[i][j] = if a[i] == b[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
         }
     }
-    if mismatches {
-        Some(result)
-    } else {
-        None
+    let mut common = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            common.push(a[i]);
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
     }
+    common
 }
 
 #[test]
@@ -74,9 +326,209 @@ fn test_checksum() {
 #[test]
 fn test_common_letters() {
     assert_eq!(
-        "fgij",
+        ("fgij".to_string(), 1),
         common_letters(vec![
             "abcde", "fghij", "klmno", "pqrst", "fguij", "axcye", "wvxyz"
         ])
     );
 }
+
+#[test]
+fn test_combinations() {
+    let mut combinations = Combinations::new(4, 2);
+    let mut all = vec![];
+    while let Some(c) = combinations.next() {
+        all.push(c.to_vec());
+    }
+    assert_eq!(
+        vec![
+            vec![0, 1],
+            vec![0, 2],
+            vec![0, 3],
+            vec![1, 2],
+            vec![1, 3],
+            vec![2, 3]
+        ],
+        all
+    );
+    assert_eq!(Some(&[][..]), Combinations::new(3, 0).next());
+}
+
+#[cfg(test)]
+fn ids(input: &[&str]) -> Vec<Vec<char>> {
+    input.iter().map(|id| id.chars().collect()).collect()
+}
+
+#[test]
+fn test_near_duplicates() {
+    let ids = ids(&["abcd", "abce", "abxe", "abcd", "zbcd", "abc"]);
+    let found: Vec<(usize, usize)> = near_duplicates(&ids, 1)
+        .iter()
+        .map(|pair| (pair.a, pair.b))
+        .collect();
+    // identical IDs 0 and 3 aren't near duplicates, and 5 is shorter
+    assert_eq!(vec![(0, 1), (0, 4), (1, 2), (1, 3), (3, 4)], found);
+    assert_eq!(
+        Pair {
+            a: 0,
+            b: 2,
+            mismatches: vec![2, 3]
+        },
+        near_duplicates(&ids, 2)[1]
+    );
+}
+
+// every pair compared directly
+#[cfg(test)]
+fn near_duplicates_quadratic(ids: &[Vec<char>], k: usize) -> Vec<(usize, usize)> {
+    let mut pairs = vec![];
+    for (a, id1) in ids.iter().enumerate() {
+        for (b, id2) in ids.iter().enumerate().skip(a + 1) {
+            if id1.len() == id2.len() && (1..=k).contains(&mismatches(id1, id2).len()) {
+                pairs.push((a, b));
+            }
+        }
+    }
+    pairs
+}
+
+#[test]
+fn test_near_duplicates_quadratic() {
+    use crate::random::XorShift;
+    // IDs from a small xorshift generator over a tiny alphabet, so that
+    // plenty of them are close
+    let mut random = XorShift::new(2018);
+    let mut next = |n| random.below(n);
+    let ids: Vec<Vec<char>> = (0..200)
+        .map(|_| {
+            let len = 4 + next(3) as usize;
+            (0..len).map(|_| (b'a' + next(3) as u8) as char).collect()
+        })
+        .collect();
+    for k in 0..=3 {
+        let found: Vec<(usize, usize)> = near_duplicates(&ids, k)
+            .iter()
+            .map(|pair| (pair.a, pair.b))
+            .collect();
+        assert_eq!(near_duplicates_quadratic(&ids, k), found, "k = {}", k);
+    }
+}
+
+#[test]
+fn test_near_duplicates_many() {
+    // numbers written as ten digits with letters for digits: 50,000 copies
+    // of 1, and 50,000 multiples of 11, no two of which differ in only one
+    // digit, so every pair is a copy of 1 and a multiple close to it
+    let as_id = |n: usize| -> Vec<char> {
+        (0..10)
+            .rev()
+            .map(|p| (b'a' + (n / 10usize.pow(p) % 10) as u8) as char)
+            .collect()
+    };
+    let mut ids: Vec<Vec<char>> = vec![as_id(1); 50_000];
+    ids.extend((0..50_000).map(|n| as_id(n * 11)));
+    let close = (0..50_000)
+        .filter(|n| mismatches(&as_id(n * 11), &as_id(1)).len() == 1)
+        .count();
+    let pairs = near_duplicates(&ids, 1);
+    assert_eq!(4, close);
+    assert_eq!(close * 50_000, pairs.len());
+    assert!(pairs.iter().all(|pair| pair.a < 50_000 && pair.b >= 50_000));
+}
+
+#[test]
+fn test_levenshtein() {
+    let distance = |a: &str, b: &str, max| levenshtein(&ids(&[a])[0], &ids(&[b])[0], max);
+    assert_eq!(Some(3), distance("kitten", "sitting", 3));
+    assert_eq!(None, distance("kitten", "sitting", 2));
+    assert_eq!(Some(1), distance("fghij", "fguij", 1));
+    assert_eq!(Some(1), distance("abcde", "abde", 1));
+    assert_eq!(Some(2), distance("", "ab", 5));
+    assert_eq!(Some(0), distance("same", "same", 0));
+    assert_eq!(None, distance("abcdef", "bcdefa", 1));
+    assert_eq!(Some(2), distance("abcdef", "bcdefa", 2));
+}
+
+// the textbook full table, to check the banded one against
+#[cfg(test)]
+fn levenshtein_full(a: &[char], b: &[char]) -> usize {
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    d[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitute = d[i - 1][j - 1] + (a[i - 1] != b[j - 1]) as usize;
+            d[i][j] = substitute.min(d[i - 1][j] + 1).min(d[i][j - 1] + 1);
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+#[test]
+fn test_levenshtein_full() {
+    use crate::random::XorShift;
+    let mut random = XorShift::new(25);
+    let mut next = |n| random.below(n);
+    for _ in 0..2000 {
+        let id = |next: &mut dyn FnMut(u32) -> u32| -> Vec<char> {
+            let len = next(7) as usize;
+            (0..len).map(|_| (b'a' + next(3) as u8) as char).collect()
+        };
+        let (a, b) = (id(&mut next), id(&mut next));
+        let max = next(5) as usize;
+        let full = levenshtein_full(&a, &b);
+        let expected = if full <= max { Some(full) } else { None };
+        assert_eq!(
+            expected,
+            levenshtein(&a, &b, max),
+            "{:?} {:?} {}",
+            a,
+            b,
+            max
+        );
+    }
+}
+
+#[test]
+fn test_lcs() {
+    let lcs = |a: &str, b: &str| -> String { lcs(&ids(&[a])[0], &ids(&[b])[0]).iter().collect() };
+    assert_eq!("fgij", lcs("fghij", "fguij"));
+    assert_eq!("bcd", lcs("abcd", "bcde"));
+    assert_eq!("", lcs("abc", "xyz"));
+}
+
+#[test]
+fn test_families() {
+    let ids = ids(&["abcde", "fghij", "abde", "klmno", "fguij", "xabde", "wvxyz"]);
+    let found = families(&ids, 1);
+    assert_eq!(2, found.len());
+    assert_eq!(vec![0, 2, 5], found[0].ids);
+    assert_eq!(vec!['a', 'b', 'd', 'e'], found[0].common);
+    assert_eq!(vec![1, 4], found[1].ids);
+    assert!(families(&ids, 0).is_empty());
+}
+
+#[test]
+fn test_run_families() {
+    let input = "abcde\nfghij\nklmno\npqrst\nfguij\naxcye\nwvxyz";
+    assert_eq!(run(Two, input).value, run_families(Two, input).value);
+}
+
+#[test]
+fn test_windows_input() {
+    use crate::input::{as_windows, normalize};
+    let input = normalize(
+        &as_windows("abcde\nfghij\nklmno\npqrst\nfguij\naxcye\nwvxyz"),
+        false,
+    );
+    assert_eq!("0", run(One, &input).value);
+    assert_eq!("fgij", run(Two, &input).value);
+}
+
+#[test]
+#[should_panic(expected = "families only solves part two")]
+fn test_run_families_part_one() {
+    run_families(One, "abcde\nabcdf");
+}
@@ -0,0 +1,197 @@
+use super::{config, Answer, Part, Part::*};
+use petgraph::graphmap::UnGraphMap;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::fmt::Write;
+
+pub fn run(part: Part, input: &str) -> Answer {
+    let facility = parse_input(input);
+    let distances = facility.distances();
+    match part {
+        One => Answer::new(distances.values().max().unwrap()).detail("rooms", distances.len()),
+        Two => {
+            let doors = config::param_usize(20, "doors", 1000);
+            Answer::new(distances.values().filter(|&&d| d >= doors).count())
+                .detail("min doors", doors)
+        }
+    }
+}
+
+// the facility map, drawn the way the puzzle does
+pub fn visualize(input: &str) -> String {
+    format!("{:?}", parse_input(input))
+}
+
+type Room = (i32, i32);
+
+// rooms are nodes, doors between them are edges
+struct Facility {
+    rooms: UnGraphMap<Room, ()>,
+}
+
+impl Facility {
+    // fewest doors to pass through to reach each room from the start
+    fn distances(&self) -> HashMap<Room, usize> {
+        let mut distances = HashMap::new();
+        let mut queue = VecDeque::new();
+        distances.insert((0, 0), 0);
+        queue.push_back((0, 0));
+        while let Some(room) = queue.pop_front() {
+            let distance = distances[&room];
+            for next in self.rooms.neighbors(room) {
+                if let Entry::Vacant(entry) = distances.entry(next) {
+                    entry.insert(distance + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+        distances
+    }
+}
+
+impl fmt::Debug for Facility {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let min_x = self.rooms.nodes().map(|r| r.0).min().unwrap();
+        let max_x = self.rooms.nodes().map(|r| r.0).max().unwrap();
+        let min_y = self.rooms.nodes().map(|r| r.1).min().unwrap();
+        let max_y = self.rooms.nodes().map(|r| r.1).max().unwrap();
+        // each room is at an odd position with walls or doors around it
+        let width = (max_x - min_x + 1) as usize * 2 + 1;
+        let height = (max_y - min_y + 1) as usize * 2 + 1;
+        let mut grid = vec![vec!['#'; width]; height];
+        for room in self.rooms.nodes() {
+            let x = (room.0 - min_x) as usize * 2 + 1;
+            let y = (room.1 - min_y) as usize * 2 + 1;
+            grid[y][x] = if room == (0, 0) { 'X' } else { '.' };
+            for next in self.rooms.neighbors(room) {
+                let door_x = (x as i32 + next.0 - room.0) as usize;
+                let door_y = (y as i32 + next.1 - room.1) as usize;
+                grid[door_y][door_x] = if next.0 == room.0 { '-' } else { '|' };
+            }
+        }
+        for row in grid {
+            for c in row {
+                f.write_char(c)?;
+            }
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+// Follows the route from the start room, adding each door passed through.
+// The route can be in several rooms at once: a branch starts from every
+// room the group was entered at, and the route after the group carries on
+// from every room any of its branches ended in.
+pub fn parse(input: &str) {
+    std::hint::black_box(parse_input(input));
+}
+
+fn parse_input(input: &str) -> Facility {
+    let route = input.trim();
+    if !route.starts_with('^') || !route.ends_with('$') {
+        panic!("parse error: route must be between ^ and $");
+    }
+    let mut rooms = UnGraphMap::new();
+    let mut current: HashSet<Room> = IntoIterator::into_iter([(0, 0)]).collect();
+    // for each open group, the rooms it was entered at and the rooms its
+    // finished branches ended in
+    let mut stack: Vec<(HashSet<Room>, HashSet<Room>)> = vec![];
+    rooms.add_node((0, 0));
+    for c in route[1..route.len() - 1].chars() {
+        let (dx, dy) = match c {
+            'N' => (0, -1),
+            'E' => (1, 0),
+            'S' => (0, 1),
+            'W' => (-1, 0),
+            '(' => {
+                stack.push((current.clone(), HashSet::new()));
+                continue;
+            }
+            '|' => {
+                let (starts, ends) = stack.last_mut().expect("parse error: | outside a group");
+                ends.extend(current.drain());
+                current = starts.clone();
+                continue;
+            }
+            ')' => {
+                let (_, mut ends) = stack.pop().expect("parse error: unbalanced )");
+                ends.extend(current.drain());
+                current = ends;
+                continue;
+            }
+            _ => panic!("parse error: unexpected {:?}", c),
+        };
+        current = current
+            .into_iter()
+            .map(|room| {
+                let next = (room.0 + dx, room.1 + dy);
+                rooms.add_edge(room, next, ());
+                next
+            })
+            .collect();
+    }
+    Facility { rooms }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{as_windows, normalize};
+
+    fn furthest(input: &str) -> usize {
+        *parse_input(input).distances().values().max().unwrap()
+    }
+
+    #[test]
+    fn test_furthest() {
+        assert_eq!(3, furthest("^WNE$"));
+        assert_eq!(8, furthest("^ENWWW(NEESS|WSSSS)$"));
+        assert_eq!(18, furthest("^ENNWSWW(NEWS|)SSSEEN(WNSE|)EE(SWEN|)NNN$"));
+        assert_eq!(
+            23,
+            furthest("^ESSWWN(E|NNENN(EESS(WNSE|)SSS|WWWSSSSE(SW|NNNE)))$")
+        );
+        assert_eq!(
+            31,
+            furthest("^WSSEESWWWNW(S|NENNEEEENN(ESSSSW(NWSW|SSEN)|WSWWN(E|WWS(E|SS))))$")
+        );
+    }
+
+    #[test]
+    fn test_route_after_group() {
+        // the last N is taken from the end of both branches
+        let facility = parse_input("^N(E|W)N$");
+        assert_eq!(6, facility.rooms.node_count());
+        assert!(facility.rooms.contains_edge((1, -1), (1, -2)));
+        assert!(facility.rooms.contains_edge((-1, -1), (-1, -2)));
+        assert_eq!(3, furthest("^N(E|W)N$"));
+    }
+
+    #[test]
+    fn test_visualize() {
+        assert_eq!(
+            "###########
+#.|.#.|.#.#
+#-###-#-#-#
+#.|.|.#.#.#
+#-#####-#-#
+#.#.#X|.#.#
+#-#-#####-#
+#.#.|.|.|.#
+#-###-###-#
+#.|.|.#.|.#
+###########
+",
+            visualize("^ENNWSWW(NEWS|)SSSEEN(WNSE|)EE(SWEN|)NNN$")
+        );
+    }
+
+    #[test]
+    fn test_windows_input() {
+        let input = normalize(&as_windows("^ENWWW(NEESS|WSSSS)$"), false);
+        assert_eq!("8", run(One, &input).value);
+        assert_eq!("0", run(Two, &input).value);
+    }
+}
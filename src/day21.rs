@@ -0,0 +1,217 @@
+use super::day16::Op::*;
+use super::day19::{parse_input, Machine, Program};
+use super::{progress, Answer, Part, Part::*};
+use std::collections::HashSet;
+
+// The program only halts when an eqrr finds register 0 equal to a value it
+// computes.  Watching what that instruction compares against gives the
+// values of register 0 that halt it: the first one compared halts it
+// soonest, and the last one before they start repeating halts it latest.
+
+pub fn run(part: Part, input: &str) -> Answer {
+    halting_value(&parse_input(input), part, true)
+}
+
+// without skipping the division loops; part 2 takes minutes like this
+pub fn run_naive(part: Part, input: &str) -> Answer {
+    halting_value(&parse_input(input), part, false)
+}
+
+fn halting_value(program: &Program, part: Part, fast: bool) -> Answer {
+    let (check, register) = halt_check(program).expect("no eqrr against register 0");
+    let division = if fast {
+        DivisionLoop::find(program)
+    } else {
+        None
+    };
+    let mut machine = Machine::new(program);
+    let mut seen = HashSet::new();
+    let mut last = None;
+    loop {
+        if machine.ip == check {
+            let value = machine.device.reg[register];
+            if part == One {
+                return Answer::new(value);
+            }
+            if !seen.insert(value) {
+                return Answer::new(last.unwrap()).detail("values before repeating", seen.len());
+            }
+            last = Some(value);
+            if seen.len().is_multiple_of(1000) {
+                progress::report("values", seen.len());
+            }
+        }
+        match division {
+            Some(division) if machine.ip == division.start => division.skip(&mut machine),
+            _ => {
+                if !machine.step() {
+                    panic!("program halted with register 0 at 0");
+                }
+            }
+        }
+    }
+}
+
+// the instruction comparing register 0 against another register, and that
+// register
+fn halt_check(program: &Program) -> Option<(usize, usize)> {
+    program
+        .insts
+        .iter()
+        .enumerate()
+        .find(|(_, inst)| inst.op == Eqrr && (inst.a == 0 || inst.b == 0))
+        .map(|(i, inst)| (i, if inst.a == 0 { inst.b } else { inst.a }))
+}
+
+// The programs divide a register by 256 by counting up a quotient until
+// (quotient + 1) * 256 is bigger than it:
+//
+//   seti 0 _ Q
+//   addi Q 1 T
+//   muli T 256 T
+//   gtrr T N T
+//   addr T ip ip
+//   addi ip 1 ip
+//   seti <end - 1> _ ip
+//   addi Q 1 Q
+//   seti <start> _ ip
+//   setr Q _ N          <- end
+//
+// That takes tens of thousands of steps per division, so it's done in one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct DivisionLoop {
+    start: usize,
+    quotient: usize,
+    temp: usize,
+    dividend: usize,
+    divisor: usize,
+}
+
+impl DivisionLoop {
+    fn find(program: &Program) -> Option<DivisionLoop> {
+        const OPS: [super::day16::Op; 10] =
+            [Seti, Addi, Muli, Gtrr, Addr, Addi, Seti, Addi, Seti, Setr];
+        program
+            .insts
+            .windows(OPS.len())
+            .enumerate()
+            .find_map(|(start, insts)| {
+                if insts.iter().map(|inst| inst.op).ne(OPS.iter().copied()) {
+                    return None;
+                }
+                let quotient = insts[0].c;
+                let temp = insts[1].c;
+                let dividend = insts[3].b;
+                let consistent = insts[0].a == 0
+                    && insts[1].a == quotient
+                    && insts[1].b == 1
+                    && (insts[2].a, insts[2].c) == (temp, temp)
+                    && (insts[3].a, insts[3].c) == (temp, temp)
+                    && insts[6].a == start + 8
+                    && insts[8].a == start
+                    && (insts[9].a, insts[9].c) == (quotient, dividend);
+                if consistent {
+                    Some(DivisionLoop {
+                        start,
+                        quotient,
+                        temp,
+                        dividend,
+                        divisor: insts[2].b,
+                    })
+                } else {
+                    None
+                }
+            })
+    }
+
+    // leave the machine as the loop would, about to execute the final setr
+    fn skip(&self, machine: &mut Machine) {
+        let reg = &mut machine.device.reg;
+        reg[self.quotient] = reg[self.dividend] / self.divisor;
+        reg[self.temp] = 1;
+        machine.ip = self.start + 9;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{as_windows, normalize};
+
+    // made up, but laid out like the puzzle inputs: a hash of register 1
+    // with a division loop at 17, compared against register 0 at 28
+    fn test_input() -> &'static str {
+        "#ip 2
+seti 123 0 1
+bani 1 456 1
+eqri 1 72 1
+addr 1 2 2
+seti 0 0 2
+seti 0 0 1
+bori 1 65536 4
+seti 1234567 0 1
+bani 4 255 3
+addr 1 3 1
+bani 1 16777215 1
+muli 1 65521 1
+bani 1 16777215 1
+gtir 256 4 3
+addr 3 2 2
+addi 2 1 2
+seti 27 0 2
+seti 0 0 3
+addi 3 1 5
+muli 5 256 5
+gtrr 5 4 5
+addr 5 2 2
+addi 2 1 2
+seti 25 0 2
+addi 3 1 3
+seti 17 0 2
+setr 3 0 4
+seti 7 0 2
+eqrr 0 1 3
+addr 3 2 2
+seti 5 0 2
+"
+    }
+
+    #[test]
+    fn test_halt_check() {
+        assert_eq!(Some((28, 1)), halt_check(&parse_input(test_input())));
+    }
+
+    #[test]
+    fn test_division_loop() {
+        assert_eq!(
+            Some(DivisionLoop {
+                start: 17,
+                quotient: 3,
+                temp: 5,
+                dividend: 4,
+                divisor: 256,
+            }),
+            DivisionLoop::find(&parse_input(test_input()))
+        );
+    }
+
+    #[test]
+    fn test_windows_input() {
+        let input = normalize(&as_windows(test_input()), false);
+        assert_eq!("10207784", run(One, &input).value);
+    }
+
+    #[test]
+    fn test_run() {
+        assert_eq!("10207784", run_naive(One, test_input()).value);
+        assert_eq!("10207784", run(One, test_input()).value);
+        assert_eq!("6994334", run(Two, test_input()).value);
+    }
+
+    // slow without the division loops skipped
+    #[ignore]
+    #[test]
+    fn test_run_naive() {
+        assert_eq!("6994334", run_naive(Two, test_input()).value);
+    }
+}
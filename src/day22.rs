@@ -0,0 +1,245 @@
+use self::Tool::*;
+use super::grid::{Grid, Point};
+use super::{Answer, Part, Part::*};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+pub fn run(part: Part, input: &str) -> Answer {
+    let mut cave = parse_input(input);
+    match part {
+        One => Answer::new(cave.risk_level()),
+        Two => {
+            let (minutes, explored) = cave.fastest_route();
+            Answer::new(minutes)
+                .detail("states explored", explored)
+                .detail(
+                    "regions mapped",
+                    cave.erosion.width() * cave.erosion.height(),
+                )
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Region {
+    Rocky,
+    Wet,
+    Narrow,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Tool {
+    Torch,
+    ClimbingGear,
+    Neither,
+}
+
+impl Region {
+    fn risk(self) -> usize {
+        self as usize
+    }
+
+    // the two tools that can be used here
+    fn tools(self) -> [Tool; 2] {
+        match self {
+            Region::Rocky => [ClimbingGear, Torch],
+            Region::Wet => [ClimbingGear, Neither],
+            Region::Narrow => [Torch, Neither],
+        }
+    }
+}
+
+const MOVE_MINUTES: usize = 1;
+const SWITCH_MINUTES: usize = 7;
+
+struct Cave {
+    depth: usize,
+    target: Point,
+    // erosion levels of the regions worked out so far, the grid grows as
+    // the search wanders further from the mouth
+    erosion: Grid<usize>,
+}
+
+impl Cave {
+    fn new(depth: usize, target: Point) -> Cave {
+        let mut cave = Cave {
+            depth,
+            target,
+            erosion: Grid::new(0, 0, 0),
+        };
+        cave.map_to(target);
+        cave
+    }
+
+    // work out the erosion level of every region up to and including p.
+    // each region needs the ones above and to the left of it, so the new
+    // ones are filled in reading order.
+    fn map_to(&mut self, p: Point) {
+        if self.erosion.contains(p) {
+            return;
+        }
+        let (old_width, old_height) = (self.erosion.width(), self.erosion.height());
+        // grow by more than needed so this doesn't happen at every step
+        let width = (p.x + 1).max(old_width * 3 / 2);
+        let height = (p.y + 1).max(old_height * 3 / 2);
+        self.erosion.grow(width, height, 0);
+        for y in 0..height {
+            for x in 0..width {
+                if x < old_width && y < old_height {
+                    continue;
+                }
+                let geologic_index = if (x, y) == (self.target.x, self.target.y) {
+                    0
+                } else if y == 0 {
+                    x * 16807
+                } else if x == 0 {
+                    y * 48271
+                } else {
+                    self.erosion[Point::new(x - 1, y)] * self.erosion[Point::new(x, y - 1)]
+                };
+                self.erosion[Point::new(x, y)] = (geologic_index + self.depth) % 20183;
+            }
+        }
+    }
+
+    fn region(&mut self, p: Point) -> Region {
+        self.map_to(p);
+        match self.erosion[p] % 3 {
+            0 => Region::Rocky,
+            1 => Region::Wet,
+            _ => Region::Narrow,
+        }
+    }
+
+    // total risk of the rectangle from the mouth to the target
+    fn risk_level(&mut self) -> usize {
+        let mut risk = 0;
+        for y in 0..=self.target.y {
+            for x in 0..=self.target.x {
+                risk += self.region(Point::new(x, y)).risk();
+            }
+        }
+        risk
+    }
+
+    // A* over (region, tool equipped), starting at the mouth with the torch
+    // and finishing at the target with the torch.  The distance to the
+    // target never overestimates the minutes left, so the first time the
+    // target comes off the queue is the fastest.  Returns the minutes and
+    // the number of states taken off the queue.
+    fn fastest_route(&mut self) -> (usize, usize) {
+        let start = (Point::new(0, 0), Torch);
+        let goal = (self.target, Torch);
+        let mut best = HashMap::new();
+        let mut queue = BinaryHeap::new();
+        let mut explored = 0;
+        best.insert(start, 0);
+        queue.push(Reverse((self.target.manhattan(start.0), 0, start)));
+        while let Some(Reverse((_, minutes, state))) = queue.pop() {
+            if state == goal {
+                return (minutes, explored);
+            }
+            if best[&state] < minutes {
+                continue;
+            }
+            explored += 1;
+            let (p, tool) = state;
+            let mut next = vec![];
+            for other in self.region(p).tools().iter() {
+                if *other != tool {
+                    next.push(((p, *other), minutes + SWITCH_MINUTES));
+                }
+            }
+            for q in p.neighbours() {
+                if self.region(q).tools().contains(&tool) {
+                    next.push(((q, tool), minutes + MOVE_MINUTES));
+                }
+            }
+            for (state, minutes) in next {
+                if best.get(&state).is_none_or(|&m| minutes < m) {
+                    best.insert(state, minutes);
+                    let estimate = minutes + self.target.manhattan(state.0);
+                    queue.push(Reverse((estimate, minutes, state)));
+                }
+            }
+        }
+        unreachable!("the target can always be reached")
+    }
+}
+
+pub fn parse(input: &str) {
+    std::hint::black_box(parse_input(input));
+}
+
+fn parse_input(input: &str) -> Cave {
+    let mut depth = None;
+    let mut target = None;
+    for line in input.lines().filter(|line| !line.is_empty()) {
+        match line.split_once(": ") {
+            Some(("depth", n)) => depth = Some(n.parse().unwrap()),
+            Some(("target", xy)) => {
+                let (x, y) = xy.split_once(',').expect("parse error: target");
+                target = Some(Point::new(x.parse().unwrap(), y.parse().unwrap()));
+            }
+            _ => panic!("parse error: {}", line),
+        }
+    }
+    Cave::new(
+        depth.expect("no depth line"),
+        target.expect("no target line"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{as_windows, normalize};
+
+    fn test_input() -> &'static str {
+        "depth: 510
+target: 10,10
+"
+    }
+
+    #[test]
+    fn test_region() {
+        let mut cave = parse_input(test_input());
+        assert_eq!(510, cave.erosion[Point::new(0, 0)]);
+        assert_eq!(17317, cave.erosion[Point::new(1, 0)]);
+        assert_eq!(8415, cave.erosion[Point::new(0, 1)]);
+        assert_eq!(1805, cave.erosion[Point::new(1, 1)]);
+        assert_eq!(510, cave.erosion[Point::new(10, 10)]);
+        assert_eq!(Region::Rocky, cave.region(Point::new(0, 0)));
+        assert_eq!(Region::Wet, cave.region(Point::new(1, 0)));
+        assert_eq!(Region::Rocky, cave.region(Point::new(0, 1)));
+        assert_eq!(Region::Narrow, cave.region(Point::new(1, 1)));
+    }
+
+    #[test]
+    fn test_map_to() {
+        // growing the map a bit at a time must give the same erosion levels
+        let mut cave = parse_input(test_input());
+        for i in 11..40 {
+            cave.map_to(Point::new(i, i / 2));
+        }
+        let mut whole = parse_input(test_input());
+        whole.map_to(Point::new(
+            cave.erosion.width() - 1,
+            cave.erosion.height() - 1,
+        ));
+        assert_eq!(whole.erosion, cave.erosion);
+    }
+
+    #[test]
+    fn test_run() {
+        assert_eq!("114", run(One, test_input()).value);
+        assert_eq!("45", run(Two, test_input()).value);
+    }
+
+    #[test]
+    fn test_windows_input() {
+        let input = normalize(&as_windows(test_input()), false);
+        assert_eq!("114", run(One, &input).value);
+        assert_eq!("45", run(Two, &input).value);
+    }
+}
@@ -0,0 +1,246 @@
+use super::{Answer, Part, Part::*};
+use regex::Regex;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+pub fn run(part: Part, input: &str) -> Answer {
+    let bots = parse_input(input);
+    match part {
+        One => {
+            let strongest = bots.iter().max_by_key(|bot| bot.r).unwrap();
+            Answer::new(
+                bots.iter()
+                    .filter(|bot| strongest.in_range(bot.pos))
+                    .count(),
+            )
+            .detail("strongest radius", strongest.r)
+        }
+        Two => {
+            let (best, in_range, explored) = best_position(&bots);
+            Answer::new(distance(best, ORIGIN))
+                .detail("position", format!("{},{},{}", best[0], best[1], best[2]))
+                .detail("bots in range", in_range)
+                .detail("cubes explored", explored)
+        }
+    }
+}
+
+type Pos = [i64; 3];
+
+fn distance(a: Pos, b: Pos) -> i64 {
+    (0..3).map(|i| (a[i] - b[i]).abs()).sum()
+}
+
+#[derive(Debug, PartialEq)]
+struct Bot {
+    pos: Pos,
+    r: i64,
+}
+
+impl Bot {
+    fn in_range(&self, pos: Pos) -> bool {
+        distance(self.pos, pos) <= self.r
+    }
+}
+
+// an axis-aligned cube of size^3 positions starting at min
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Cube {
+    min: Pos,
+    size: i64,
+}
+
+impl Cube {
+    // the position in the cube closest to pos
+    fn closest(&self, pos: Pos) -> Pos {
+        let mut closest = pos;
+        for (i, c) in closest.iter_mut().enumerate() {
+            *c = (*c).clamp(self.min[i], self.min[i] + self.size - 1);
+        }
+        closest
+    }
+
+    // how many bots reach at least one position in the cube
+    fn bots_in_range(&self, bots: &[Bot]) -> usize {
+        bots.iter()
+            .filter(|bot| bot.in_range(self.closest(bot.pos)))
+            .count()
+    }
+
+    fn split(&self) -> impl Iterator<Item = Cube> + '_ {
+        let half = self.size / 2;
+        (0..8).map(move |corner: usize| {
+            let mut min = self.min;
+            for (i, c) in min.iter_mut().enumerate() {
+                if corner & (1 << i) != 0 {
+                    *c += half;
+                }
+            }
+            Cube { min, size: half }
+        })
+    }
+}
+
+// Starts from a single cube, a power of two on a side, that encloses every
+// bot's range, and keeps splitting the most promising cube into eight.  A
+// cube's count of bots in range is at least the count at any position inside
+// it, so the first single position to come off the queue is in range of as
+// many bots as any position can be.  Smaller cubes go first so the search
+// dives down to a position quickly, then carries on through the cubes with as
+// many bots in range that could hold a position nearer the origin.  Returns
+// the position, how many bots are in range of it and how many cubes were
+// split.
+fn best_position(bots: &[Bot]) -> (Pos, usize, usize) {
+    let extent = bots
+        .iter()
+        .flat_map(|bot| bot.pos.iter().map(move |c| c.abs() + bot.r))
+        .max()
+        .unwrap_or(0);
+    let mut size = 1;
+    while size < 2 * extent + 1 {
+        size *= 2;
+    }
+    let start = Cube {
+        min: [-size / 2; 3],
+        size,
+    };
+    let mut queue = BinaryHeap::new();
+    let mut explored = 0;
+    let mut best: Option<(Pos, usize)> = None;
+    queue.push(prioritise(start, bots));
+    while let Some((in_range, _, Reverse(nearest), cube)) = queue.pop() {
+        if let Some((pos, most)) = best {
+            if in_range < most {
+                break;
+            }
+            if nearest >= distance(pos, ORIGIN) {
+                continue;
+            }
+        }
+        if cube.size == 1 {
+            best = Some((cube.min, in_range));
+            continue;
+        }
+        explored += 1;
+        for child in cube.split() {
+            queue.push(prioritise(child, bots));
+        }
+    }
+    let (pos, in_range) = best.expect("the search always ends at a single position");
+    (pos, in_range, explored)
+}
+
+const ORIGIN: Pos = [0, 0, 0];
+
+type Prioritised = (usize, Reverse<i64>, Reverse<i64>, Cube);
+
+// most bots in range first, then smallest, then nearest the origin
+fn prioritise(cube: Cube, bots: &[Bot]) -> Prioritised {
+    (
+        cube.bots_in_range(bots),
+        Reverse(cube.size),
+        Reverse(distance(cube.closest(ORIGIN), ORIGIN)),
+        cube,
+    )
+}
+
+pub fn parse(input: &str) {
+    std::hint::black_box(parse_input(input));
+}
+
+fn parse_input(input: &str) -> Vec<Bot> {
+    let re = Regex::new(r"^pos=<(-?\d+),(-?\d+),(-?\d+)>, r=(\d+)$").unwrap();
+    let mut bots = vec![];
+    for line in input.lines() {
+        match re.captures(line) {
+            Some(cap) => bots.push(Bot {
+                pos: [
+                    cap[1].parse().unwrap(),
+                    cap[2].parse().unwrap(),
+                    cap[3].parse().unwrap(),
+                ],
+                r: cap[4].parse().unwrap(),
+            }),
+            None => eprintln!("parse error: {}", line),
+        }
+    }
+    bots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{as_windows, normalize};
+
+    fn test_input() -> &'static str {
+        "pos=<0,0,0>, r=4
+pos=<1,0,0>, r=1
+pos=<4,0,0>, r=3
+pos=<0,2,0>, r=1
+pos=<0,5,0>, r=3
+pos=<0,0,3>, r=1
+pos=<1,1,1>, r=1
+pos=<1,1,2>, r=1
+pos=<1,3,1>, r=1
+"
+    }
+
+    fn test_input_two() -> &'static str {
+        "pos=<10,12,12>, r=2
+pos=<12,14,12>, r=2
+pos=<16,12,12>, r=4
+pos=<14,14,14>, r=6
+pos=<50,50,50>, r=200
+pos=<10,10,10>, r=5
+"
+    }
+
+    #[test]
+    fn test_parse_input() {
+        let bots = parse_input(test_input());
+        assert_eq!(9, bots.len());
+        assert_eq!(
+            Bot {
+                pos: [1, 3, 1],
+                r: 1
+            },
+            bots[8]
+        );
+    }
+
+    #[test]
+    fn test_run() {
+        assert_eq!("7", run(One, test_input()).value);
+        assert_eq!("36", run(Two, test_input_two()).value);
+    }
+
+    #[test]
+    fn test_best_position() {
+        let (best, in_range, _) = best_position(&parse_input(test_input_two()));
+        assert_eq!([12, 12, 12], best);
+        assert_eq!(5, in_range);
+    }
+
+    #[test]
+    fn test_split() {
+        let cube = Cube {
+            min: [-2, -2, -2],
+            size: 4,
+        };
+        let children: Vec<Cube> = cube.split().collect();
+        assert_eq!(8, children.len());
+        assert!(children.iter().all(|child| child.size == 2));
+        assert!(children.contains(&Cube {
+            min: [0, -2, 0],
+            size: 2
+        }));
+    }
+
+    #[test]
+    fn test_windows_input() {
+        let input = normalize(&as_windows(test_input()), false);
+        assert_eq!("7", run(One, &input).value);
+        let input = normalize(&as_windows(test_input_two()), false);
+        assert_eq!("36", run(Two, &input).value);
+    }
+}
@@ -0,0 +1,281 @@
+use self::Army::*;
+use super::{search, Answer, Part, Part::*};
+use regex::Regex;
+use std::cmp::Reverse;
+
+pub fn run(part: Part, input: &str) -> Answer {
+    let battle = parse_input(input);
+    match part {
+        One => {
+            let mut battle = battle;
+            match battle.fight() {
+                Outcome::Won(army, units) => Answer::new(units)
+                    .detail("winner", format!("{:?}", army))
+                    .detail("rounds", battle.rounds),
+                Outcome::Stalemate => panic!("no army can win without a boost"),
+            }
+        }
+        Two => {
+            let (boost, (units, rounds)) = search::smallest_from(1, "boost", |boost| {
+                let mut battle = battle.clone();
+                battle.boost(boost as usize);
+                match battle.fight() {
+                    Outcome::Won(ImmuneSystem, units) => Some((units, battle.rounds)),
+                    _ => None,
+                }
+            });
+            Answer::new(units)
+                .detail("boost", boost)
+                .detail("rounds", rounds)
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Army {
+    ImmuneSystem,
+    Infection,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Group {
+    army: Army,
+    units: usize,
+    hp: usize,
+    damage: usize,
+    attack: String,
+    initiative: usize,
+    weak: Vec<String>,
+    immune: Vec<String>,
+}
+
+impl Group {
+    fn effective_power(&self) -> usize {
+        self.units * self.damage
+    }
+
+    // what this group would deal to the target if it attacked now, before
+    // the target's hit points are taken into account
+    fn damage_to(&self, target: &Group) -> usize {
+        if target.immune.contains(&self.attack) {
+            0
+        } else if target.weak.contains(&self.attack) {
+            self.effective_power() * 2
+        } else {
+            self.effective_power()
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Outcome {
+    // the army left standing and how many units it has
+    Won(Army, usize),
+    // neither side can kill any more units of the other
+    Stalemate,
+}
+
+#[derive(Clone, Debug)]
+struct Battle {
+    groups: Vec<Group>,
+    rounds: usize,
+}
+
+impl Battle {
+    fn boost(&mut self, boost: usize) {
+        for group in self.groups.iter_mut().filter(|g| g.army == ImmuneSystem) {
+            group.damage += boost;
+        }
+    }
+
+    fn fight(&mut self) -> Outcome {
+        loop {
+            let first = self.groups[0].army;
+            if self.groups.iter().all(|g| g.army == first) {
+                return Outcome::Won(first, self.groups.iter().map(|g| g.units).sum());
+            }
+            if self.round() == 0 {
+                return Outcome::Stalemate;
+            }
+        }
+    }
+
+    // Each group picks the enemy it would deal the most damage to, biggest
+    // effective power choosing first, then they attack in order of
+    // initiative.  Returns the number of units killed.
+    fn round(&mut self) -> usize {
+        let mut choosers: Vec<usize> = (0..self.groups.len()).collect();
+        choosers.sort_by_key(|&i| {
+            let group = &self.groups[i];
+            Reverse((group.effective_power(), group.initiative))
+        });
+        let mut targets: Vec<Option<usize>> = vec![None; self.groups.len()];
+        let mut chosen = vec![false; self.groups.len()];
+        for &i in choosers.iter() {
+            let attacker = &self.groups[i];
+            let target = (0..self.groups.len())
+                .filter(|&j| !chosen[j] && self.groups[j].army != attacker.army)
+                .filter(|&j| attacker.damage_to(&self.groups[j]) > 0)
+                .max_by_key(|&j| {
+                    let defender = &self.groups[j];
+                    (
+                        attacker.damage_to(defender),
+                        defender.effective_power(),
+                        defender.initiative,
+                    )
+                });
+            if let Some(j) = target {
+                chosen[j] = true;
+                targets[i] = Some(j);
+            }
+        }
+
+        let mut attackers: Vec<usize> = (0..self.groups.len()).collect();
+        attackers.sort_by_key(|&i| Reverse(self.groups[i].initiative));
+        let mut killed = 0;
+        for i in attackers {
+            // a group killed earlier in the round doesn't get to attack
+            if self.groups[i].units == 0 {
+                continue;
+            }
+            if let Some(j) = targets[i] {
+                let damage = self.groups[i].damage_to(&self.groups[j]);
+                let defender = &mut self.groups[j];
+                let kills = (damage / defender.hp).min(defender.units);
+                defender.units -= kills;
+                killed += kills;
+            }
+        }
+        self.groups.retain(|g| g.units > 0);
+        self.rounds += 1;
+        killed
+    }
+}
+
+pub fn parse(input: &str) {
+    std::hint::black_box(parse_input(input));
+}
+
+fn parse_input(input: &str) -> Battle {
+    let re = Regex::new(
+        r"^(\d+) units each with (\d+) hit points (?:\((.*)\) )?with an attack that does (\d+) (\w+) damage at initiative (\d+)$",
+    )
+    .unwrap();
+    let mut army = None;
+    let mut groups = vec![];
+    for line in input.lines() {
+        match line {
+            "Immune System:" => army = Some(ImmuneSystem),
+            "Infection:" => army = Some(Infection),
+            "" => (),
+            _ => match re.captures(line) {
+                Some(cap) => {
+                    let mut group = Group {
+                        army: army.expect("parse error: group before an army"),
+                        units: cap[1].parse().unwrap(),
+                        hp: cap[2].parse().unwrap(),
+                        damage: cap[4].parse().unwrap(),
+                        attack: cap[5].to_string(),
+                        initiative: cap[6].parse().unwrap(),
+                        weak: vec![],
+                        immune: vec![],
+                    };
+                    if let Some(traits) = cap.get(3) {
+                        parse_traits(&mut group, traits.as_str());
+                    }
+                    groups.push(group);
+                }
+                None => panic!("parse error: {}", line),
+            },
+        }
+    }
+    Battle { groups, rounds: 0 }
+}
+
+// e.g. "weak to radiation, bludgeoning; immune to fire"
+fn parse_traits(group: &mut Group, traits: &str) {
+    for part in traits.split("; ") {
+        let (list, types) = if let Some(types) = part.strip_prefix("weak to ") {
+            (&mut group.weak, types)
+        } else if let Some(types) = part.strip_prefix("immune to ") {
+            (&mut group.immune, types)
+        } else {
+            panic!("parse error: {}", traits);
+        };
+        list.extend(types.split(", ").map(|t| t.to_string()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{as_windows, normalize};
+
+    fn test_input() -> &'static str {
+        "Immune System:
+17 units each with 5390 hit points (weak to radiation, bludgeoning) with an attack that does 4507 fire damage at initiative 2
+989 units each with 1274 hit points (immune to fire; weak to bludgeoning, slashing) with an attack that does 25 slashing damage at initiative 3
+
+Infection:
+801 units each with 4706 hit points (weak to radiation) with an attack that does 116 bludgeoning damage at initiative 1
+4485 units each with 2961 hit points (immune to radiation; weak to fire, cold) with an attack that does 12 slashing damage at initiative 4
+"
+    }
+
+    #[test]
+    fn test_parse_input() {
+        let battle = parse_input(test_input());
+        assert_eq!(4, battle.groups.len());
+        let group = &battle.groups[1];
+        assert_eq!(ImmuneSystem, group.army);
+        assert_eq!(vec!["fire"], group.immune);
+        assert_eq!(vec!["bludgeoning", "slashing"], group.weak);
+        assert_eq!(Infection, battle.groups[2].army);
+        assert_eq!(92916, battle.groups[2].effective_power());
+    }
+
+    #[test]
+    fn test_round() {
+        let mut battle = parse_input(test_input());
+        battle.round();
+        let units: Vec<usize> = battle.groups.iter().map(|g| g.units).collect();
+        assert_eq!(vec![905, 797, 4434], units);
+    }
+
+    #[test]
+    fn test_fight() {
+        assert_eq!(
+            Outcome::Won(Infection, 5216),
+            parse_input(test_input()).fight()
+        );
+        let mut battle = parse_input(test_input());
+        battle.boost(1570);
+        assert_eq!(Outcome::Won(ImmuneSystem, 51), battle.fight());
+    }
+
+    #[test]
+    fn test_stalemate() {
+        // each side is immune to the other's attack
+        let mut battle = parse_input(
+            "Immune System:
+10 units each with 10 hit points (immune to cold) with an attack that does 5 fire damage at initiative 2
+
+Infection:
+10 units each with 10 hit points (immune to fire) with an attack that does 5 cold damage at initiative 1
+",
+        );
+        assert_eq!(Outcome::Stalemate, battle.fight());
+    }
+
+    #[test]
+    fn test_run() {
+        assert_eq!("5216", run(One, test_input()).value);
+        assert_eq!("51", run(Two, test_input()).value);
+    }
+
+    #[test]
+    fn test_windows_input() {
+        let input = normalize(&as_windows(test_input()), false);
+        assert_eq!("5216", run(One, &input).value);
+    }
+}
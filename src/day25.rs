@@ -0,0 +1,140 @@
+use super::sets::DisjointSets;
+use super::{Answer, Part, Part::*};
+
+pub fn run(part: Part, input: &str) -> Answer {
+    let points = parse_input(input);
+    match part {
+        One => Answer::new(constellations(&points)).detail("points", points.len()),
+        Two => unreachable!("day 25 has no part 2"),
+    }
+}
+
+type Point = [i32; 4];
+
+fn distance(a: &Point, b: &Point) -> i32 {
+    a.iter().zip(b.iter()).map(|(a, b)| (a - b).abs()).sum()
+}
+
+// points within 3 of each other are in the same constellation, as are any
+// points joined through a chain of them
+fn constellations(points: &[Point]) -> usize {
+    let mut sets = DisjointSets::new(points.len());
+    for (i, a) in points.iter().enumerate() {
+        for (j, b) in points.iter().enumerate().skip(i + 1) {
+            if distance(a, b) <= 3 {
+                sets.union(i, j);
+            }
+        }
+    }
+    sets.count()
+}
+
+pub fn parse(input: &str) {
+    std::hint::black_box(parse_input(input));
+}
+
+fn parse_input(input: &str) -> Vec<Point> {
+    input
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let coords: Vec<i32> = line
+                .split(',')
+                .map(|c| {
+                    c.trim()
+                        .parse()
+                        .unwrap_or_else(|_| panic!("parse error: {}", line))
+                })
+                .collect();
+            match coords.as_slice() {
+                &[x, y, z, t] => [x, y, z, t],
+                _ => panic!("parse error: {}", line),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{as_windows, normalize};
+
+    fn test_input() -> &'static str {
+        " 0,0,0,0
+ 3,0,0,0
+ 0,3,0,0
+ 0,0,3,0
+ 0,0,0,3
+ 0,0,0,6
+ 9,0,0,0
+12,0,0,0
+"
+    }
+
+    #[test]
+    fn test_parse_input() {
+        let points = parse_input(test_input());
+        assert_eq!(8, points.len());
+        assert_eq!([12, 0, 0, 0], points[7]);
+    }
+
+    #[test]
+    fn test_constellations() {
+        assert_eq!(2, constellations(&parse_input(test_input())));
+        assert_eq!(
+            4,
+            constellations(&parse_input(
+                "-1,2,2,0
+0,0,2,-2
+0,0,0,-2
+-1,2,0,0
+-2,-2,-2,2
+3,0,2,-1
+-1,3,2,2
+-1,0,-1,0
+0,2,1,-2
+3,0,0,0
+"
+            ))
+        );
+        assert_eq!(
+            3,
+            constellations(&parse_input(
+                "1,-1,0,1
+2,0,-1,0
+3,2,-1,0
+0,0,3,1
+0,0,-1,-1
+2,3,-2,0
+-2,2,0,0
+2,-2,0,-1
+1,-1,0,-1
+3,2,0,2
+"
+            ))
+        );
+        assert_eq!(
+            8,
+            constellations(&parse_input(
+                "1,-1,-1,-2
+-2,-2,0,1
+0,2,1,3
+-2,3,-2,1
+0,2,3,-2
+-1,-1,1,-2
+0,-2,-1,0
+-2,2,3,-1
+1,2,2,0
+-1,-2,0,-2
+"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_windows_input() {
+        let input = normalize(&as_windows(test_input()), false);
+        assert_eq!("2", run(One, &input).value);
+    }
+}
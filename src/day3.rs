@@ -1,62 +1,172 @@
-use super::{Part, Part::*};
+use super::grid::{Grid, Point};
+use super::{Answer, Part, Part::*};
 use regex::Regex;
-use std::collections::hash_map::Entry;
-use std::collections::HashMap;
 
-pub fn run(part: Part, input: &str) {
+pub fn run(part: Part, input: &str) -> Answer {
     let claims = parse_input(input);
-    let mut fabric = HashMap::new();
-    let overlapping_squares = process(&mut fabric, &claims);
+    let fabric = Fabric::new(&claims);
     match part {
-        One => println!("{}", overlapping_squares),
-        Two => println!("{}", intact_claim(&fabric, &claims)),
+        One => Answer::new(fabric.overlapping).detail("model", format!("{:?}", fabric.model)),
+        Two => Answer::new(
+            claims
+                .iter()
+                .zip(fabric.intact.iter())
+                .find(|(_, &intact)| intact)
+                .map_or(0, |(claim, _)| claim.id),
+        )
+        .detail("model", format!("{:?}", fabric.model)),
     }
 }
 
-fn process(fabric: &mut HashMap<(usize, usize), Square>, claims: &Vec<Claim>) -> i32 {
-    let mut overlap_count = 0;
-    for claim in claims {
-        for x in claim.left..(claim.left + claim.width) {
-            for y in claim.top..(claim.top + claim.height) {
-                match fabric.entry((x, y)) {
-                    Entry::Occupied(ent) => {
-                        let sq = ent.into_mut();
-                        if !sq.overlaps {
-                            overlap_count += 1;
-                            sq.overlaps = true;
-                        }
-                        sq.content = claim.id;
-                    }
-                    Entry::Vacant(ent) => {
-                        ent.insert(Square {
-                            content: claim.id,
-                            overlaps: false,
-                        });
-                    }
+// A bounding box up to this many square inches is counted an inch at a time,
+// anything bigger is swept a claim edge at a time.
+const DENSE_LIMIT: usize = 1 << 22;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Model {
+    Dense,
+    SweepLine,
+}
+
+#[derive(Debug, PartialEq)]
+struct Fabric {
+    model: Model,
+    // square inches in two or more claims
+    overlapping: usize,
+    // whether each claim, in order, has no square inch in any other
+    intact: Vec<bool>,
+}
+
+impl Fabric {
+    fn new(claims: &[Claim]) -> Fabric {
+        let width = claims.iter().map(|c| c.left + c.width).max().unwrap_or(0);
+        let height = claims.iter().map(|c| c.top + c.height).max().unwrap_or(0);
+        if width.saturating_mul(height) <= DENSE_LIMIT {
+            Fabric::dense(claims, width, height)
+        } else {
+            Fabric::sweep_line(claims)
+        }
+    }
+
+    // Counts the claims on every square inch with a difference array, +1 at
+    // a claim's top left corner and so on, then a summed-area table of the
+    // overlapping inches answers for any claim without walking it.
+    fn dense(claims: &[Claim], width: usize, height: usize) -> Fabric {
+        let mut count: Grid<i32> = Grid::new(width + 1, height + 1, 0);
+        for c in claims {
+            let (right, bottom) = (c.left + c.width, c.top + c.height);
+            count[Point::new(c.left, c.top)] += 1;
+            count[Point::new(right, c.top)] -= 1;
+            count[Point::new(c.left, bottom)] -= 1;
+            count[Point::new(right, bottom)] += 1;
+        }
+        // overlaps[x, y] is the number of overlapping inches above and left
+        // of (x, y)
+        let mut overlaps: Grid<usize> = Grid::new(width + 1, height + 1, 0);
+        for y in 0..height {
+            for x in 0..width {
+                let p = Point::new(x, y);
+                if x > 0 {
+                    count[p] += count[Point::new(x - 1, y)];
                 }
+                if y > 0 {
+                    count[p] += count[Point::new(x, y - 1)];
+                }
+                if x > 0 && y > 0 {
+                    count[p] -= count[Point::new(x - 1, y - 1)];
+                }
+                overlaps[Point::new(x + 1, y + 1)] = (count[p] >= 2) as usize
+                    + overlaps[Point::new(x, y + 1)]
+                    + overlaps[Point::new(x + 1, y)]
+                    - overlaps[p];
             }
         }
+        let intact = claims
+            .iter()
+            .map(|c| {
+                let (right, bottom) = (c.left + c.width, c.top + c.height);
+                overlaps[Point::new(right, bottom)] + overlaps[Point::new(c.left, c.top)]
+                    == overlaps[Point::new(c.left, bottom)] + overlaps[Point::new(right, c.top)]
+            })
+            .collect();
+        Fabric {
+            model: Model::Dense,
+            overlapping: overlaps[Point::new(width, height)],
+            intact,
+        }
     }
-    return overlap_count;
-}
 
-fn intact_claim(fabric: &HashMap<(usize, usize), Square>, claims: &Vec<Claim>) -> usize {
-    'claim: for claim in claims {
-        for x in claim.left..(claim.left + claim.width) {
-            for y in claim.top..(claim.top + claim.height) {
-                match fabric.get(&(x, y)) {
-                    Some(sq) => {
-                        if sq.overlaps {
-                            continue 'claim;
-                        }
+    // Sweeps left to right over the claims' left and right edges, keeping the
+    // claims that cover the current column.  Between edges every column is
+    // the same, so its overlap is measured once and multiplied up, and each
+    // claim is checked against the others it meets as it joins.
+    fn sweep_line(claims: &[Claim]) -> Fabric {
+        // (x, joining, claim), with claims leaving before others join at
+        // the same x as their right edge isn't part of them
+        let mut edges = vec![];
+        for (i, c) in claims.iter().enumerate() {
+            if c.width > 0 && c.height > 0 {
+                edges.push((c.left, true, i));
+                edges.push((c.left + c.width, false, i));
+            }
+        }
+        edges.sort_unstable();
+        let mut intact = vec![true; claims.len()];
+        let mut active: Vec<usize> = vec![];
+        let mut overlapping = 0;
+        let mut x = 0;
+        for (edge_x, joining, i) in edges {
+            if edge_x > x {
+                overlapping += (edge_x - x) * column_overlap(claims, &active);
+                x = edge_x;
+            }
+            if joining {
+                let c = &claims[i];
+                for &j in active.iter() {
+                    let other = &claims[j];
+                    if c.top < other.top + other.height && other.top < c.top + c.height {
+                        intact[i] = false;
+                        intact[j] = false;
                     }
-                    None => {}
                 }
+                active.push(i);
+            } else {
+                active.retain(|&j| j != i);
             }
         }
-        return claim.id;
+        Fabric {
+            model: Model::SweepLine,
+            overlapping,
+            intact,
+        }
+    }
+}
+
+// how many inches of a column are in two or more of the given claims
+fn column_overlap(claims: &[Claim], active: &[usize]) -> usize {
+    let mut edges: Vec<(usize, i32)> = active
+        .iter()
+        .flat_map(|&i| {
+            let c = &claims[i];
+            [(c.top, 1), (c.top + c.height, -1)]
+        })
+        .collect();
+    edges.sort_unstable();
+    let mut overlap = 0;
+    let mut depth = 0;
+    let mut y = 0;
+    for (edge_y, change) in edges {
+        if depth >= 2 {
+            overlap += edge_y - y;
+        }
+        depth += change;
+        y = edge_y;
     }
-    0
+    overlap
+}
+
+pub fn parse(input: &str) {
+    std::hint::black_box(parse_input(input));
 }
 
 fn parse_input(input: &str) -> Vec<Claim> {
@@ -86,11 +196,86 @@ struct Claim {
     height: usize,
 }
 
-#[derive(Copy, Clone)]
-struct Square {
-    content: usize,
-    overlaps: bool,
+#[cfg(test)]
+fn test_input() -> &'static str {
+    "#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2"
 }
 
 #[test]
-fn test_run() {}
+fn test_run() {
+    assert_eq!("4", run(One, test_input()).value);
+    assert_eq!("3", run(Two, test_input()).value);
+}
+
+#[test]
+fn test_models() {
+    let claims = parse_input(test_input());
+    let dense = Fabric::dense(&claims, 7, 7);
+    let sweep = Fabric::sweep_line(&claims);
+    assert_eq!(Model::Dense, Fabric::new(&claims).model);
+    assert_eq!(4, dense.overlapping);
+    assert_eq!(vec![false, false, true], dense.intact);
+    assert_eq!(dense.overlapping, sweep.overlapping);
+    assert_eq!(dense.intact, sweep.intact);
+
+    // far too big to count an inch at a time
+    let huge = parse_input("#1 @ 0,0: 100000x100000\n#2 @ 99999,99999: 100000x100000");
+    let fabric = Fabric::new(&huge);
+    assert_eq!(Model::SweepLine, fabric.model);
+    assert_eq!(1, fabric.overlapping);
+    assert_eq!(vec![false, false], fabric.intact);
+}
+
+// every square inch of every claim counted in a map, as it used to be
+#[cfg(test)]
+fn by_square_inch(claims: &[Claim]) -> (usize, Vec<bool>) {
+    use std::collections::HashMap;
+    let mut fabric: HashMap<(usize, usize), usize> = HashMap::new();
+    fn squares(c: &Claim) -> impl Iterator<Item = (usize, usize)> {
+        let (left, top, height) = (c.left, c.top, c.height);
+        (left..left + c.width).flat_map(move |x| (top..top + height).map(move |y| (x, y)))
+    }
+    for c in claims {
+        for square in squares(c) {
+            *fabric.entry(square).or_insert(0) += 1;
+        }
+    }
+    let overlapping = fabric.values().filter(|&&n| n >= 2).count();
+    let intact = claims
+        .iter()
+        .map(|c| squares(c).all(|square| fabric[&square] == 1))
+        .collect();
+    (overlapping, intact)
+}
+
+#[test]
+fn test_by_square_inch() {
+    use crate::random::XorShift;
+    // claims from a small xorshift generator, crowded enough to overlap
+    let mut random = XorShift::new(3);
+    let mut next = |n| random.below(n) as usize;
+    for _ in 0..200 {
+        let claims: Vec<Claim> = (1..=1 + next(12))
+            .map(|id| Claim {
+                id,
+                left: next(20),
+                top: next(20),
+                width: next(8),
+                height: next(8),
+            })
+            .collect();
+        let expected = by_square_inch(&claims);
+        let dense = Fabric::dense(&claims, 28, 28);
+        let sweep = Fabric::sweep_line(&claims);
+        assert_eq!(expected, (dense.overlapping, dense.intact), "{:?}", claims);
+        assert_eq!(expected, (sweep.overlapping, sweep.intact), "{:?}", claims);
+    }
+}
+
+#[test]
+fn test_windows_input() {
+    use crate::input::{as_windows, normalize};
+    let input = normalize(&as_windows(test_input()), false);
+    assert_eq!("4", run(One, &input).value);
+    assert_eq!("3", run(Two, &input).value);
+}
@@ -1,15 +1,19 @@
-use super::{Part, Part::*};
+use super::{Answer, Part, Part::*};
 use chrono::prelude::*;
 use std::collections::HashMap;
 use time::Duration;
 
-pub fn run(part: Part, input: &str) {
+pub fn run(part: Part, input: &str) -> Answer {
     let stats = collect_stats(&parse_input(input));
-    let result = match part {
+    let (guard, minute) = match part {
         One => strategy1(&stats),
         Two => strategy2(&stats),
     };
-    println!("{}", result.0 * result.1);
+    Answer::new(guard * minute)
+        .detail("guard", guard)
+        .detail("minute", minute)
+        .detail("times asleep at minute", stats[&guard].1[minute as usize])
+        .detail("total minutes asleep", stats[&guard].0)
 }
 
 enum Observation {
@@ -77,6 +81,10 @@ fn strategy2(stats: &HashMap<i32, (i32, [i32; 60])>) -> (i32, i32) {
     most_sleepy
 }
 
+pub fn parse(input: &str) {
+    std::hint::black_box(parse_input(input));
+}
+
 fn parse_input(input: &str) -> Vec<Event> {
     let mut events = vec![];
     for line in input.lines() {
@@ -95,7 +103,7 @@ fn parse_input(input: &str) -> Vec<Event> {
             let guard: i32 = event
                 .chars()
                 .skip(7)
-                .take_while(|c| c.is_digit(10))
+                .take_while(|c| c.is_ascii_digit())
                 .collect::<String>()
                 .parse()
                 .unwrap();
@@ -141,4 +149,14 @@ fn test_run() {
     let stats = collect_stats(&parse_input(test_input));
     assert_eq!((10, 24), strategy1(&stats));
     assert_eq!((99, 45), strategy2(&stats));
+
+    let windows = crate::input::as_windows(test_input);
+    let input = crate::input::normalize(&windows, false);
+    assert_eq!("4455", run(Two, &input).value);
+    let answer = run(One, &input);
+    assert_eq!("240", answer.value);
+    assert_eq!(
+        "  guard: 10\n  minute: 24\n  times asleep at minute: 2\n  total minutes asleep: 50\n",
+        answer.explain()
+    );
 }
@@ -1,34 +1,44 @@
-use super::{Part, Part::*};
-use std::collections::VecDeque;
-use std::iter::FromIterator;
+use super::{Answer, Part, Part::*};
+use std::io::{BufRead, Read};
 
-pub fn run(part: Part, input: &str) {
-    match part {
-        One => println!("{}", react(input).len()),
-        Two => println!("{}", shortest_polymer(input).len()),
-    }
+pub fn run(part: Part, input: &str) -> Answer {
+    solve(part, react(input))
+}
+
+// only the reacted polymer is kept in memory.  Removing a type and then
+// reacting gives the same result from the reacted polymer as from the
+// original, so part 2 starts from there too.
+pub fn stream(part: Part, input: &mut dyn BufRead) -> Result<Answer, String> {
+    Ok(solve(
+        part,
+        react_units(Read::bytes(input).map(|b| b.unwrap())),
+    ))
+}
+
+fn solve(part: Part, polymer: String) -> Answer {
+    Answer::new(match part {
+        One => polymer.len(),
+        Two => shortest_polymer(&polymer).len(),
+    })
 }
 
 fn react(input: &str) -> String {
-    let mut input = VecDeque::from_iter(input.chars());
-    if *input.get(input.len() - 1).unwrap() == '\n' {
-        input.pop_back();
-    }
-    let mut output = VecDeque::new();
-    output.push_front(input.pop_front().unwrap());
-    loop {
-        while !input.is_empty()
-            && !output.is_empty()
-            && is_pair(*input.front().unwrap(), *output.back().unwrap())
-        {
-            input.pop_front();
-            output.pop_back();
-        }
-        if input.is_empty() {
-            return output.into_iter().collect();
+    react_units(input.bytes())
+}
+
+// each unit either cancels the one on top of the stack or goes on it;
+// anything that isn't a unit (newlines) is skipped
+fn react_units<I: Iterator<Item = u8>>(input: I) -> String {
+    let mut output: Vec<u8> = vec![];
+    for unit in input.filter(|b| b.is_ascii_alphabetic()) {
+        match output.last() {
+            Some(&top) if is_pair(unit, top) => {
+                output.pop();
+            }
+            _ => output.push(unit),
         }
-        output.push_back(input.pop_front().unwrap());
     }
+    String::from_utf8(output).unwrap()
 }
 
 fn remove_types(input: &str, uc: char) -> String {
@@ -51,8 +61,8 @@ fn shortest_polymer(input: &str) -> String {
     }
 }
 
-fn is_pair(a: char, b: char) -> bool {
-    a != b && a.to_ascii_uppercase() == b.to_ascii_uppercase()
+fn is_pair(a: u8, b: u8) -> bool {
+    a != b && a.eq_ignore_ascii_case(&b)
 }
 
 #[test]
@@ -66,6 +76,19 @@ fn test_react() {
     assert_eq!(10, react("dabAcCaCBAcCcaDA\n").len());
 }
 
+#[test]
+fn test_react_crlf() {
+    let windows = crate::input::as_windows("dabAcCaCBAcCcaDA");
+    assert_eq!("dabCBAcaDA", react(&windows));
+}
+
+#[test]
+fn test_stream_crlf() {
+    let input = "dabAcCaCBAcCcaDA\r\n\r\n";
+    assert_eq!("10", stream(One, &mut input.as_bytes()).unwrap().value);
+    assert_eq!("4", stream(Two, &mut input.as_bytes()).unwrap().value);
+}
+
 #[test]
 fn test_remove_type() {
     assert_eq!("dbcCCBcCcD", remove_types("dabAcCaCBAcCcaDA", 'A'));
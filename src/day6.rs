@@ -1,11 +1,18 @@
-use super::{Part, Part::*};
+use super::{config, Answer, Part, Part::*};
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 
-pub fn run(part: Part, input: &str) {
+pub fn run(part: Part, input: &str) -> Answer {
+    Answer::new(match part {
+        One => largest_finite_area(&parse_input(input)),
+        Two => safe_area(&parse_input(input), config::param_usize(6, "limit", 10000)),
+    })
+}
+
+pub fn run_flood(part: Part, input: &str) -> Answer {
     match part {
-        One => println!("{}", largest_finite_area(&parse_input(input))),
-        Two => println!("{}", safe_area(&parse_input(input), 10000)),
+        One => Answer::new(largest_finite_area_flood(&parse_input(input))),
+        Two => unreachable!("flood fill only solves part one"),
     }
 }
 
@@ -21,7 +28,7 @@ struct Closest {
     coord: Option<usize>,
 }
 
-fn largest_finite_area(coords: &Vec<Point>) -> usize {
+fn largest_finite_area(coords: &[Point]) -> usize {
     let (min, max) = box_size(coords);
 
     let mut closest: HashMap<Point, Closest> = HashMap::new();
@@ -29,7 +36,7 @@ fn largest_finite_area(coords: &Vec<Point>) -> usize {
         for x in min.x..=max.x {
             for y in min.y..=max.y {
                 let distance = ((coord.x - x).abs() + (coord.y - y).abs()) as usize;
-                match closest.entry(Point { x: x, y: y }) {
+                match closest.entry(Point { x, y }) {
                     Entry::Occupied(ent) => {
                         let ent = ent.into_mut();
                         if distance < ent.distance {
@@ -41,7 +48,7 @@ fn largest_finite_area(coords: &Vec<Point>) -> usize {
                     }
                     Entry::Vacant(ent) => {
                         ent.insert(Closest {
-                            distance: distance,
+                            distance,
                             coord: Some(c),
                         });
                     }
@@ -50,12 +57,75 @@ fn largest_finite_area(coords: &Vec<Point>) -> usize {
         }
     }
 
+    largest_area(coords.len(), min, max, |p| closest.get(&p).unwrap().coord)
+}
+
+// same answer as largest_finite_area, but grows every coordinate's region
+// outwards one step at a time (multi-source BFS) instead of measuring the
+// distance from every coordinate to every cell.  a cell reached in the same
+// step from different regions, or from a tied cell, is itself tied.
+fn largest_finite_area_flood(coords: &[Point]) -> usize {
+    let (min, max) = box_size(coords);
+    let width = (max.x - min.x + 1) as usize;
+    let height = (max.y - min.y + 1) as usize;
+    let index = |p: Point| (p.y - min.y) as usize * width + (p.x - min.x) as usize;
+
+    let mut cells: Vec<Option<Closest>> = (0..width * height).map(|_| None).collect();
+    let mut frontier = vec![];
+    for (c, &coord) in coords.iter().enumerate() {
+        cells[index(coord)] = Some(Closest {
+            distance: 0,
+            coord: Some(c),
+        });
+        frontier.push(coord);
+    }
+
+    let mut distance = 0;
+    while !frontier.is_empty() {
+        distance += 1;
+        let mut next = vec![];
+        for p in frontier {
+            let coord = cells[index(p)].as_ref().unwrap().coord;
+            for &(dx, dy) in &[(0, -1), (-1, 0), (1, 0), (0, 1)] {
+                let n = Point {
+                    x: p.x + dx,
+                    y: p.y + dy,
+                };
+                if n.x < min.x || n.x > max.x || n.y < min.y || n.y > max.y {
+                    continue;
+                }
+                match &mut cells[index(n)] {
+                    Some(cell) => {
+                        if cell.distance == distance && cell.coord != coord {
+                            cell.coord = None;
+                        }
+                    }
+                    cell => {
+                        *cell = Some(Closest { distance, coord });
+                        next.push(n);
+                    }
+                }
+            }
+        }
+        frontier = next;
+    }
+
+    largest_area(coords.len(), min, max, |p| {
+        cells[index(p)].as_ref().unwrap().coord
+    })
+}
+
+// size of the largest region which doesn't touch the edge of the bounding
+// box.  `closest` gives the coordinate closest to a point, if there is one.
+fn largest_area<F>(count: usize, min: Point, max: Point, closest: F) -> usize
+where
+    F: Fn(Point) -> Option<usize>,
+{
     // areas, Some(x) means finite area x, None means infinite
-    let mut areas: Vec<Option<usize>> = vec![Some(0); coords.len()];
+    let mut areas: Vec<Option<usize>> = vec![Some(0); count];
     for y in min.y..=max.y {
         for x in min.x..=max.x {
-            let closest = closest.get(&Point { x: x, y: y }).unwrap();
-            if let Some(c) = closest.coord {
+            if let Some(c) = closest(Point { x, y }) {
                 if y == min.y || y == max.y || x == min.x || x == max.x {
                     areas[c] = None;
                 } else if let Some(v) = areas[c] {
@@ -64,10 +134,10 @@ fn largest_finite_area(coords: &Vec<Point>) -> usize {
             }
         }
     }
-    areas.into_iter().filter_map(|area| area).max().unwrap()
+    areas.into_iter().flatten().max().unwrap()
 }
 
-fn safe_area(coords: &Vec<Point>, limit: usize) -> usize {
+fn safe_area(coords: &[Point], limit: usize) -> usize {
     let (min, max) = box_size(coords);
     let mut area = 0;
     for x in min.x..=max.x {
@@ -85,7 +155,7 @@ fn safe_area(coords: &Vec<Point>, limit: usize) -> usize {
     area
 }
 
-fn box_size(coords: &Vec<Point>) -> (Point, Point) {
+fn box_size(coords: &[Point]) -> (Point, Point) {
     let mut min = *coords.first().unwrap();
     let mut max = *coords.first().unwrap();
     for c in coords.iter().skip(1) {
@@ -103,6 +173,10 @@ fn box_size(coords: &Vec<Point>) -> (Point, Point) {
     (min, max)
 }
 
+pub fn parse(input: &str) {
+    std::hint::black_box(parse_input(input));
+}
+
 fn parse_input(input: &str) -> Vec<Point> {
     let mut list = vec![];
     for line in input.lines() {
@@ -124,5 +198,11 @@ fn test_run() {
 5, 5
 8, 9";
     assert_eq!(17, largest_finite_area(&parse_input(test_input)));
+    assert_eq!(17, largest_finite_area_flood(&parse_input(test_input)));
     assert_eq!(16, safe_area(&parse_input(test_input), 32));
+
+    let windows = crate::input::as_windows(test_input);
+    let input = crate::input::normalize(&windows, false);
+    assert_eq!("17", run(One, &input).value);
+    assert_eq!("17", run_flood(One, &input).value);
 }
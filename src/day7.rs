@@ -1,11 +1,17 @@
-use super::{Part, Part::*};
+use super::{config, Answer, Part, Part::*};
 use petgraph::graphmap::DiGraphMap;
 use petgraph::Incoming;
 
-pub fn run(part: Part, input: &str) {
+pub fn run(part: Part, input: &str) -> Answer {
     match part {
-        One => println!("{}", get_order(parse_input(input))),
-        Two => println!("{}", time_simulation(parse_input(input), 5, 60)),
+        One => Answer::new(get_order(parse_input(input))),
+        Two => {
+            let workers = config::param_usize(7, "workers", 5);
+            let base_time = config::param_usize(7, "base_time", 60);
+            Answer::new(time_simulation(parse_input(input), workers, base_time))
+                .detail("workers", workers)
+                .detail("base time", base_time)
+        }
     }
 }
 
@@ -60,10 +66,10 @@ fn time_simulation(mut g: DiGraphMap<char, ()>, num_workers: usize, base_time: u
 }
 
 fn next_step(g: &DiGraphMap<char, ()>) -> Option<char> {
-    next_step_except(g, &vec![])
+    next_step_except(g, &[])
 }
 
-fn next_step_except(g: &DiGraphMap<char, ()>, except: &Vec<char>) -> Option<char> {
+fn next_step_except(g: &DiGraphMap<char, ()>, except: &[char]) -> Option<char> {
     let mut next: Vec<_> = g
         .nodes()
         .filter(|n| g.neighbors_directed(*n, Incoming).count() == 0)
@@ -77,6 +83,10 @@ fn next_step_except(g: &DiGraphMap<char, ()>, except: &Vec<char>) -> Option<char
     }
 }
 
+pub fn parse(input: &str) {
+    std::hint::black_box(parse_input(input));
+}
+
 fn parse_input(input: &str) -> DiGraphMap<char, ()> {
     let mut g = DiGraphMap::new();
     for line in input.lines() {
@@ -100,3 +110,13 @@ Step F must be finished before step E can begin.";
     assert_eq!("CABDFE", get_order(parse_input(test_input)));
     assert_eq!(15, time_simulation(parse_input(test_input), 2, 0));
 }
+
+#[test]
+fn test_parse_input_crlf() {
+    let test_input = "Step C must be finished before step A can begin.
+Step A must be finished before step B can begin.";
+    let windows = crate::input::as_windows(test_input);
+    let g = parse_input(&crate::input::normalize(&windows, false));
+    let edges: Vec<(char, char)> = g.all_edges().map(|(a, b, _)| (a, b)).collect();
+    assert_eq!(vec![('C', 'A'), ('A', 'B')], edges);
+}
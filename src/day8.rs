@@ -1,61 +1,63 @@
-use super::{Part, Part::*};
-
-pub fn run(part: Part, input: &str) {
-    let input = parse_input(input);
-    println!(
-        "{}",
-        match part {
-            One => metadata_sum(&input),
-            Two => value(&input),
-        }
-    );
+use super::{input, Answer, Part, Part::*};
+use std::io::BufRead;
+
+pub fn run(part: Part, input: &str) -> Answer {
+    solve(part, parse_input(&mut input.as_bytes()))
+}
+
+// the tree is summarised as it's read, so only the path down to the current
+// node (and the values of its children) is ever held
+pub fn stream(part: Part, reader: &mut dyn BufRead) -> Result<Answer, String> {
+    Ok(solve(part, parse_input(reader)))
+}
+
+fn solve<I: Iterator<Item = usize>>(part: Part, input: I) -> Answer {
+    Answer::new(match part {
+        One => metadata_sum(input),
+        Two => value(input),
+    })
 }
 
-fn parse_input(input: &str) -> Vec<usize> {
-    input
-        .split(" ")
-        .filter_map(|s| s.trim().parse().ok())
-        .collect()
+pub fn parse(input: &str) {
+    std::hint::black_box(parse_input(&mut input.as_bytes()).collect::<Vec<_>>());
 }
 
-fn metadata_sum(input: &[usize]) -> usize {
-    parse_tree(input).sum
+fn parse_input(reader: &mut dyn BufRead) -> impl Iterator<Item = usize> + '_ {
+    input::tokens(reader).filter_map(|s| s.parse().ok())
 }
 
-fn value(input: &[usize]) -> usize {
-    parse_tree(input).value
+fn metadata_sum<I: Iterator<Item = usize>>(mut input: I) -> usize {
+    parse_tree(&mut input).sum
+}
+
+fn value<I: Iterator<Item = usize>>(mut input: I) -> usize {
+    parse_tree(&mut input).value
 }
 
 struct Info {
-    used: usize,
     sum: usize,
     value: usize,
 }
 
-fn parse_tree(input: &[usize]) -> Info {
-    let num_children = input[0];
-    let num_metadata = input[1];
-    let mut info = Info {
-        used: 2,
-        sum: 0,
-        value: 0,
-    };
+// consumes one node (and its children) from the input
+fn parse_tree<I: Iterator<Item = usize>>(input: &mut I) -> Info {
+    let num_children = input.next().unwrap();
+    let num_metadata = input.next().unwrap();
+    let mut info = Info { sum: 0, value: 0 };
     let mut children = vec![];
 
     for _ in 0..num_children {
-        let child = parse_tree(&input[info.used..]);
+        let child = parse_tree(input);
         info.sum += child.sum;
-        info.used += child.used;
-        children.push(child);
+        children.push(child.value);
     }
 
-    for i in input.iter().skip(info.used).take(num_metadata) {
+    for i in input.take(num_metadata) {
         info.sum += i;
-        info.used += 1;
         if num_children == 0 {
             info.value += i;
         } else if i - 1 < children.len() {
-            info.value += children[i - 1].value;
+            info.value += children[i - 1];
         }
     }
 
@@ -72,11 +74,18 @@ mod tests {
 
     #[test]
     fn test_metadata_sum() {
-        assert_eq!(138, metadata_sum(&parse_input(test_input())));
+        assert_eq!(138, metadata_sum(parse_input(&mut test_input().as_bytes())));
     }
 
     #[test]
     fn test_value() {
-        assert_eq!(66, value(&parse_input(test_input())));
+        assert_eq!(66, value(parse_input(&mut test_input().as_bytes())));
+    }
+
+    #[test]
+    fn test_stream_crlf() {
+        let input = "2 3 0 3 10 11 12\r\n1 1 0 1 99 2 1 1 2\r\n\r\n";
+        assert_eq!("138", stream(One, &mut input.as_bytes()).unwrap().value);
+        assert_eq!("66", stream(Two, &mut input.as_bytes()).unwrap().value);
     }
 }
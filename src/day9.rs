@@ -1,17 +1,20 @@
-use super::{Part, Part::*};
+use super::{Answer, Part, Part::*};
 use std::collections::VecDeque;
 
-pub fn run(part: Part, input: &str) {
+pub fn run(part: Part, input: &str) -> Answer {
     let input = parse_input(input);
-    match part {
-        One => println!("{}", simulate_game(input[0], input[1])),
-        Two => println!("{}", simulate_game(input[0], input[1] * 100)),
-    }
+    let (players, last_marble) = match part {
+        One => (input[0], input[1]),
+        Two => (input[0], input[1] * 100),
+    };
+    Answer::new(simulate_game(players, last_marble))
+        .detail("players", players)
+        .detail("last marble", last_marble)
 }
 
 fn simulate_game(players: usize, last_marble: usize) -> usize {
     let mut game: VecDeque<usize> = VecDeque::with_capacity(last_marble);
-    let mut scores = vec![0; players as usize];
+    let mut scores = vec![0; players];
     game.push_front(0);
 
     for marble in 1..=last_marble {
@@ -40,6 +43,10 @@ fn shift(game: &mut VecDeque<usize>, times: i32, forward: bool) {
     }
 }
 
+pub fn parse(input: &str) {
+    std::hint::black_box(parse_input(input));
+}
+
 fn parse_input(input: &str) -> Vec<usize> {
     input
         .split_whitespace()
@@ -56,3 +63,13 @@ fn test_simulate_game() {
     assert_eq!(54718, simulate_game(21, 6111));
     assert_eq!(37305, simulate_game(30, 5807));
 }
+
+#[test]
+fn test_windows_input() {
+    use crate::input::{as_windows, normalize};
+    let input = normalize(
+        &as_windows("10 players; last marble is worth 1618 points"),
+        false,
+    );
+    assert_eq!("8317", run(One, &input).value);
+}
@@ -0,0 +1,122 @@
+// Points and rectangular grids for the days played out on a map.
+
+use std::ops::{Index, IndexMut};
+
+// order is important for derived ordering (reading order = row first)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub y: usize,
+    pub x: usize,
+}
+
+impl Point {
+    pub fn new(x: usize, y: usize) -> Point {
+        Point { x, y }
+    }
+
+    // the points above, left, right and below, in reading order, leaving
+    // out any that would be off the top or left edge
+    pub fn neighbours(self) -> impl Iterator<Item = Point> {
+        let up = self.y.checked_sub(1).map(|y| Point::new(self.x, y));
+        let left = self.x.checked_sub(1).map(|x| Point::new(x, self.y));
+        let right = Some(Point::new(self.x + 1, self.y));
+        let down = Some(Point::new(self.x, self.y + 1));
+        IntoIterator::into_iter([up, left, right, down]).flatten()
+    }
+
+    pub fn manhattan(self, other: Point) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+}
+
+// A width by height grid stored a row at a time.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T> {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        p.x < self.width && p.y < self.height
+    }
+
+    // make the grid at least width by height, keeping what's already in it
+    // where it is and filling the new cells
+    pub fn grow(&mut self, width: usize, height: usize, fill: T) {
+        let width = width.max(self.width);
+        let height = height.max(self.height);
+        let mut cells = Vec::with_capacity(width * height);
+        for row in self.cells.chunks(self.width.max(1)).take(self.height) {
+            cells.extend_from_slice(row);
+            cells.resize(cells.len() + width - self.width, fill.clone());
+        }
+        cells.resize(width * height, fill);
+        *self = Grid {
+            width,
+            height,
+            cells,
+        };
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        assert!(p.x < self.width, "{:?} outside the grid", p);
+        &self.cells[p.y * self.width + p.x]
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        assert!(p.x < self.width, "{:?} outside the grid", p);
+        &mut self.cells[p.y * self.width + p.x]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_neighbours() {
+        let around: Vec<Point> = Point::new(0, 1).neighbours().collect();
+        assert_eq!(
+            vec![Point::new(0, 0), Point::new(1, 1), Point::new(0, 2)],
+            around
+        );
+        assert_eq!(4, Point::new(3, 3).neighbours().count());
+        assert_eq!(5, Point::new(1, 4).manhattan(Point::new(3, 1)));
+    }
+
+    #[test]
+    fn test_grow() {
+        let mut grid = Grid::new(2, 2, 0);
+        grid[Point::new(1, 0)] = 1;
+        grid[Point::new(0, 1)] = 2;
+        grid.grow(3, 3, 9);
+        assert_eq!((3, 3), (grid.width(), grid.height()));
+        assert_eq!(vec![0, 1, 9, 2, 0, 9, 9, 9, 9], grid.cells);
+        assert!(grid.contains(Point::new(2, 2)));
+        assert!(!grid.contains(Point::new(3, 0)));
+    }
+}
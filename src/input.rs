@@ -0,0 +1,150 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+// Inputs saved on other machines can start with a byte order mark, have
+// CRLF line endings or extra blank lines at the end, none of which the day
+// parsers expect.  normalize() turns all of that into plain lines, each
+// ending in \n, before any parser sees the input.
+//
+// Trailing whitespace within lines is kept unless `trim_lines` is set, as
+// some maps (day 13) have significant spaces at the end of lines, and a line
+// of nothing but spaces is only blank once it has been trimmed.
+pub fn normalize(input: &str, trim_lines: bool) -> String {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let mut output = String::with_capacity(input.len());
+    let mut blank_lines = 0;
+    for line in input.split('\n') {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let line = if trim_lines { line.trim_end() } else { line };
+        if line.is_empty() {
+            // only kept if something follows them
+            blank_lines += 1;
+            continue;
+        }
+        for _ in 0..blank_lines {
+            output.push('\n');
+        }
+        blank_lines = 0;
+        output.push_str(line);
+        output.push('\n');
+    }
+    output
+}
+
+// Solvers that can work through input too large to hold in memory take a
+// BufRead instead of a &str.  normalize() can't be applied to a stream, so
+// they have to tolerate CRLF and blank lines themselves; open() takes care
+// of the byte order mark.
+pub fn open<P: AsRef<Path>>(path: P) -> io::Result<BufReader<File>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    if reader.fill_buf()?.starts_with(BOM) {
+        reader.consume(BOM.len());
+    }
+    Ok(reader)
+}
+
+const BOM: &[u8] = "\u{feff}".as_bytes();
+
+// whitespace separated words from a stream, read a buffer at a time
+pub struct Tokens<R> {
+    reader: R,
+}
+
+pub fn tokens<R: BufRead>(reader: R) -> Tokens<R> {
+    Tokens { reader }
+}
+
+impl<R: BufRead> Iterator for Tokens<R> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        let mut token = vec![];
+        loop {
+            let buf = self.reader.fill_buf().unwrap();
+            if buf.is_empty() {
+                break;
+            }
+            // the whitespace after a token is left for the next call to skip
+            let mut used = 0;
+            let mut ended = false;
+            for &b in buf {
+                if !b.is_ascii_whitespace() {
+                    token.push(b);
+                } else if !token.is_empty() {
+                    ended = true;
+                    break;
+                }
+                used += 1;
+            }
+            self.reader.consume(used);
+            if ended {
+                break;
+            }
+        }
+        if token.is_empty() {
+            None
+        } else {
+            Some(String::from_utf8(token).unwrap())
+        }
+    }
+}
+
+// the input as a Windows editor might have saved it: with a byte order mark,
+// CRLF line endings and a trailing blank line
+#[cfg(test)]
+pub fn as_windows(input: &str) -> String {
+    format!("\u{feff}{}\r\n", input.replace('\n', "\r\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!("a\nb\n", normalize("a\nb", false));
+        assert_eq!("a\nb\n", normalize("a\r\nb\r\n", false));
+        assert_eq!("a\nb\n", normalize("\u{feff}a\nb\n", false));
+        assert_eq!("a\n\nb\n", normalize("a\r\n\r\nb\r\n\r\n \r\n", true));
+        assert_eq!("a\n\nb\n\n \n", normalize("a\r\n\r\nb\r\n\r\n \r\n", false));
+        assert_eq!("a \nb\n", normalize("a \r\nb\r\n", false));
+        assert_eq!("a\nb\n", normalize("a \r\nb\t\r\n", true));
+        assert_eq!("  a\n", normalize("  a", true));
+        assert_eq!("", normalize("\r\n\n", false));
+    }
+
+    #[test]
+    fn test_normalize_space_rows() {
+        // a row of a map that is all track-less spaces is still a row
+        let map = "/-\\ \r\n    \r\n\\-/ \r\n";
+        assert_eq!("/-\\ \n    \n\\-/ \n", normalize(map, false));
+        assert_eq!("/-\\\n\n\\-/\n", normalize(map, true));
+    }
+
+    #[test]
+    fn test_as_windows() {
+        let input = "#..\n..#\n";
+        assert_eq!("\u{feff}#..\r\n..#\r\n\r\n", as_windows(input));
+        assert_eq!(input, normalize(&as_windows(input), false));
+    }
+
+    #[test]
+    fn test_tokens() {
+        let words: Vec<String> = tokens(" 2 3\r\n0  13\n\n".as_bytes()).collect();
+        assert_eq!(vec!["2", "3", "0", "13"], words);
+        // tokens split across reads of a small buffer
+        let reader = BufReader::with_capacity(2, "10 200 3000".as_bytes());
+        let words: Vec<String> = tokens(reader).collect();
+        assert_eq!(vec!["10", "200", "3000"], words);
+    }
+
+    #[test]
+    fn test_open() {
+        let path = std::env::temp_dir().join(format!("aoc-input-{}.txt", std::process::id()));
+        std::fs::write(&path, as_windows("+1\n-2\n")).unwrap();
+        let lines: Vec<String> = open(&path).unwrap().lines().map(|l| l.unwrap()).collect();
+        assert_eq!(vec!["+1", "-2", ""], lines);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
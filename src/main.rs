@@ -1,56 +1,563 @@
 use std::env;
-use std::fs::File;
+use std::fmt;
+use std::fs::{self, File};
 use std::io::prelude::*;
+use std::path::PathBuf;
 use std::process;
+use std::time::{Duration, Instant};
 
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Part {
     One,
     Two,
 }
 
-fn main() {
-    let args: Vec<_> = env::args().collect();
-    if args.len() != 3 {
-        eprintln!("Usage: {} <day> <part>", env::args().nth(0).unwrap());
-        process::exit(1);
-    }
-    let day: i32 = env::args().nth(1).unwrap().parse().unwrap();
-    let part = env::args().nth(2).unwrap();
-    let part = match part.as_ref() {
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+// The answer to one part of a puzzle, along with any intermediate results
+// behind it that are worth showing with --explain.
+pub struct Answer {
+    pub value: String,
+    pub details: Vec<(String, String)>,
+}
+
+impl Answer {
+    pub fn new<T: fmt::Display>(value: T) -> Answer {
+        Answer {
+            value: value.to_string(),
+            details: vec![],
+        }
+    }
+
+    pub fn detail<N: fmt::Display, T: fmt::Display>(mut self, name: N, value: T) -> Answer {
+        self.details.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn explain(&self) -> String {
+        let mut s = String::new();
+        for (name, value) in self.details.iter() {
+            s.push_str(&format!("  {}: {}\n", name, value));
+        }
+        s
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.value)
+    }
+}
+
+fn usage() -> ! {
+    let prog = env::args().next().unwrap();
+    eprintln!("Options for all commands: [--config <file>] [--profile <name>]");
+    eprintln!(
+        "Usage: {} [--impl <name>] [--explain] [--mem] <day> <part>",
+        prog
+    );
+    eprintln!("       {} [--mem] all", prog);
+    eprintln!("       {} [--mem] [--runs <n>] bench <day> [<part>]", prog);
+    eprintln!("       {} crosscheck <day> [<part>]", prog);
+    eprintln!(
+        "       {} [--impl <name>] [--answer <answer>] [--endpoint <url>] submit <day> <part>",
+        prog
+    );
+    eprintln!("       {} fetch <day>", prog);
+    eprintln!("       {} list", prog);
+    eprintln!("       {} dashboard", prog);
+    eprintln!("       {} visualize <day>", prog);
+    eprintln!(
+        "       {} [--impl <name>] [--input <file>] watch <day> [<part>]",
+        prog
+    );
+    process::exit(1);
+}
+
+fn parse_day(arg: &str) -> u32 {
+    match arg.parse() {
+        Ok(day) => day,
+        Err(_) => {
+            eprintln!("Day must be a number");
+            process::exit(1);
+        }
+    }
+}
+
+fn parse_part(arg: &str) -> Part {
+    match arg {
         "1" => Part::One,
         "2" => Part::Two,
         _ => {
             eprintln!("Part must be 1 or 2");
-            std::process::exit(1);
+            process::exit(1);
         }
-    };
+    }
+}
 
-    let filename = format!("input/day{}.txt", day);
-    let mut f = File::open(filename).unwrap();
+// remove a flag from the argument list, returning whether it was there
+fn take_flag(args: &mut Vec<String>, flag: &str) -> bool {
+    match args.iter().position(|arg| arg == flag) {
+        Some(i) => {
+            args.remove(i);
+            true
+        }
+        None => false,
+    }
+}
+
+// remove an option and its value from the argument list
+fn take_option(args: &mut Vec<String>, option: &str) -> Option<String> {
+    let i = args.iter().position(|arg| arg == option)?;
+    if i + 1 >= args.len() {
+        usage();
+    }
+    let value = args.remove(i + 1);
+    args.remove(i);
+    Some(value)
+}
+
+// the parts to run: the one given on the command line, or both
+fn parse_parts(arg: Option<&String>) -> Vec<Part> {
+    match arg {
+        Some(part) => vec![parse_part(part)],
+        None => vec![Part::One, Part::Two],
+    }
+}
+
+// every parser gets its input normalized (see input.rs)
+fn read_input(day: u32) -> String {
+    let mut f = File::open(config::get().input_path(day)).unwrap();
     let mut input = String::new();
     f.read_to_string(&mut input).unwrap();
+    input::normalize(&input, config::get().trim_lines)
+}
+
+// time a closure, and count its allocations if --mem was given
+fn measure<T, F: FnOnce() -> T>(f: F) -> (T, Duration, Option<alloc::Usage>) {
+    let start = Instant::now();
+    let (result, usage) = alloc::measure(f);
+    progress::finish();
+    (result, start.elapsed(), usage)
+}
+
+fn format_stats(elapsed: Duration, usage: Option<alloc::Usage>) -> String {
+    match usage {
+        Some(usage) => format!("{:.3}s, {}", elapsed.as_secs_f64(), usage),
+        None => format!("{:.3}s", elapsed.as_secs_f64()),
+    }
+}
+
+fn main() {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let name = take_option(&mut args, "--impl");
+    let runs = take_option(&mut args, "--runs");
+    let answer = take_option(&mut args, "--answer");
+    let endpoint = take_option(&mut args, "--endpoint");
+    let config_file = take_option(&mut args, "--config");
+    let profile = take_option(&mut args, "--profile");
+    let input = take_option(&mut args, "--input");
+    let explain = take_flag(&mut args, "--explain");
+    if take_flag(&mut args, "--mem") {
+        alloc::enable();
+    }
+    progress::enable();
+
+    let mut config = match config::Config::load(config_file.as_deref().unwrap_or(config::FILENAME))
+    {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    };
+    if profile.is_some() {
+        config.profile = profile;
+    }
+    config::set(config);
+
+    match args.first().map(|arg| arg.as_str()) {
+        Some("crosscheck") => {
+            if args.len() < 2 || args.len() > 3 || name.is_some() || explain {
+                usage();
+            }
+            let day = parse_day(&args[1]);
+            if !crosscheck(day, &parse_parts(args.get(2))) {
+                process::exit(1);
+            }
+        }
+        Some("all") => {
+            if args.len() != 1 || name.is_some() || explain {
+                usage();
+            }
+            run_all();
+        }
+        Some("bench") => {
+            if args.len() < 2 || args.len() > 3 || explain {
+                usage();
+            }
+            let runs = match runs.map(|runs| runs.parse()) {
+                None => 10,
+                Some(Ok(runs)) if runs > 0 => runs,
+                Some(_) => {
+                    eprintln!("Runs must be a positive number");
+                    process::exit(1);
+                }
+            };
+            let day = parse_day(&args[1]);
+            bench(day, &parse_parts(args.get(2)), name.as_deref(), runs);
+        }
+        Some("submit") => {
+            if args.len() != 3 || explain {
+                usage();
+            }
+            let day = parse_day(&args[1]);
+            let part = parse_part(&args[2]);
+            let answer = match answer {
+                Some(answer) => answer,
+                None => match registry::find(day, part, name.as_deref())
+                    .and_then(|variant| variant.solve(part, &read_input(day)))
+                {
+                    Ok(answer) => answer.value,
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        process::exit(1);
+                    }
+                },
+            };
+            let endpoint = endpoint
+                .or_else(|| env::var("AOC_ENDPOINT").ok())
+                .unwrap_or_else(|| config::get().endpoint.clone());
+            if !submit(&endpoint, day, part, &answer) {
+                process::exit(1);
+            }
+        }
+        Some("fetch") => {
+            if args.len() != 2 || explain {
+                usage();
+            }
+            let endpoint = endpoint
+                .or_else(|| env::var("AOC_ENDPOINT").ok())
+                .unwrap_or_else(|| config::get().endpoint.clone());
+            if !fetch(&endpoint, parse_day(&args[1])) {
+                process::exit(1);
+            }
+        }
+        Some("list") => {
+            if args.len() != 1 || name.is_some() || explain {
+                usage();
+            }
+            list();
+        }
+        Some("dashboard") => {
+            if args.len() != 1 || name.is_some() || explain {
+                usage();
+            }
+            progress::disable();
+            if let Err(e) = dashboard::run() {
+                eprintln!("{}", e);
+                process::exit(1);
+            }
+        }
+        Some("visualize") => {
+            if args.len() != 2 || name.is_some() || explain {
+                usage();
+            }
+            let day = parse_day(&args[1]);
+            match registry::visualization(day) {
+                Some(visualize) => print!("{}", visualize(&read_input(day))),
+                None => {
+                    eprintln!("Day {} has no visualization", day);
+                    process::exit(1);
+                }
+            }
+        }
+        Some("watch") => {
+            if args.len() < 2 || args.len() > 3 || explain {
+                usage();
+            }
+            let day = parse_day(&args[1]);
+            let path = match input {
+                Some(input) => PathBuf::from(input),
+                None => config::get().input_path(day),
+            };
+            watch::watch(day, &parse_parts(args.get(2)), name.as_deref(), &path);
+        }
+        Some(_) if args.len() == 2 => {
+            let day = parse_day(&args[0]);
+            let part = parse_part(&args[1]);
+            match registry::find(day, part, name.as_deref()) {
+                Ok(variant) => {
+                    let answer = match variant.stream {
+                        // never loads the whole input
+                        Some(stream) => {
+                            let mut reader = input::open(config::get().input_path(day)).unwrap();
+                            progress::start(format!("Day {} Part {}", day, part));
+                            measure(|| stream(part, &mut reader)).0
+                        }
+                        None => {
+                            let input = read_input(day);
+                            progress::start(format!("Day {} Part {}", day, part));
+                            measure(|| variant.solve(part, &input)).0
+                        }
+                    };
+                    match answer {
+                        Ok(answer) => {
+                            println!("{}", answer);
+                            if explain {
+                                print!("{}", answer.explain());
+                            }
+                        }
+                        Err(e) => {
+                            eprintln!("Day {} Part {}: {}", day, part, e);
+                            process::exit(1);
+                        }
+                    }
+                }
+                Err(e) => {
+                    eprintln!("{}", e);
+                    process::exit(1);
+                }
+            }
+        }
+        _ => usage(),
+    }
+}
+
+// run both parts of every day that has an input file
+fn run_all() {
+    for day in 1..=25 {
+        if registry::variants(day, Part::One).is_empty() || !config::get().input_path(day).exists()
+        {
+            continue;
+        }
+        let (input, elapsed, usage) = measure(|| read_input(day));
+        println!(
+            "Day {} Read input: {} bytes ({})",
+            day,
+            input.len(),
+            format_stats(elapsed, usage)
+        );
+        if let Some(parse) = registry::parser(day) {
+            let ((), elapsed, usage) = measure(|| parse(&input));
+            println!("Day {} Parse: {}", day, format_stats(elapsed, usage));
+        }
+        // each part parses the input again, so its numbers include parsing
+        for &part in &[Part::One, Part::Two] {
+            // day 25 only has a part 1
+            let variant = match registry::find(day, part, None) {
+                Ok(variant) => variant,
+                Err(_) => continue,
+            };
+            progress::start(format!("Day {} Part {}", day, part));
+            let (answer, elapsed, usage) = measure(|| variant.solve(part, &input));
+            // a day with no answer doesn't stop the days after it
+            let answer =
+                answer.map_or_else(|e| format!("error: {}", e), |answer| answer.to_string());
+            println!(
+                "Day {} Part {}: {} ({})",
+                day,
+                part,
+                answer,
+                format_stats(elapsed, usage)
+            );
+        }
+    }
+}
+
+// the implementations of each day, built in and from plugins
+fn list() {
+    for day in 1..=25 {
+        for &part in &[Part::One, Part::Two] {
+            let names = registry::names(day, part);
+            if !names.is_empty() {
+                println!("Day {} Part {}: {}", day, part, names.join(", "));
+            }
+        }
+    }
+}
+
+// run a solver several times and report the spread of timings
+fn bench(day: u32, parts: &[Part], name: Option<&str>, runs: usize) {
+    let input = read_input(day);
+    if let Some(parse) = registry::parser(day) {
+        let mut times = vec![];
+        let mut memory = None;
+        for _ in 0..runs {
+            let ((), elapsed, usage) = measure(|| parse(&input));
+            times.push(elapsed);
+            memory = memory.or(usage);
+        }
+        report_runs(&format!("Day {} Parse", day), &times, memory);
+    }
+    for &part in parts {
+        let variant = match registry::find(day, part, name) {
+            Ok(variant) => variant,
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(1);
+            }
+        };
+        progress::start(format!("Day {} Part {}", day, part));
+        let mut times = vec![];
+        let mut memory = None;
+        for _ in 0..runs {
+            let (answer, elapsed, usage) = measure(|| variant.solve(part, &input));
+            if let Err(e) = answer {
+                eprintln!("Day {} Part {}: {}", day, part, e);
+                process::exit(1);
+            }
+            times.push(elapsed);
+            memory = memory.or(usage);
+        }
+        report_runs(
+            &format!("Day {} Part {} [{}]", day, part, variant.name),
+            &times,
+            memory,
+        );
+    }
+}
+
+fn report_runs(label: &str, times: &[Duration], memory: Option<alloc::Usage>) {
+    let min = times.iter().min().unwrap();
+    let max = times.iter().max().unwrap();
+    let mean = times.iter().sum::<Duration>() / times.len() as u32;
+    print!(
+        "{} {} runs: min {:.3}s, mean {:.3}s, max {:.3}s",
+        label,
+        times.len(),
+        min.as_secs_f64(),
+        mean.as_secs_f64(),
+        max.as_secs_f64()
+    );
+    match memory {
+        Some(usage) => println!(", {}", usage),
+        None => println!(),
+    }
+}
+
+// session cookie from $AOC_SESSION, or the .session file
+fn session() -> Option<String> {
+    env::var("AOC_SESSION")
+        .ok()
+        .or_else(|| fs::read_to_string(".session").ok())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+fn submit(endpoint: &str, day: u32, part: Part, answer: &str) -> bool {
+    let session = match session() {
+        Some(session) => session,
+        None => {
+            eprintln!("Set AOC_SESSION or write the session cookie to .session");
+            return false;
+        }
+    };
+    let path = config::get().profile_dir().join("submissions.tsv");
+    let mut history = match submit::History::load(&path) {
+        Ok(history) => history,
+        Err(e) => {
+            eprintln!("{}: {}", path.display(), e);
+            process::exit(1);
+        }
+    };
+    println!("Day {} Part {}: submitting {}", day, part, answer);
+    match submit::submit(&mut history, endpoint, &session, day, part, answer) {
+        Ok(verdict) => {
+            println!("{:?}", verdict);
+            verdict == submit::Verdict::Right
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            false
+        }
+    }
+}
 
-    match day {
-        1 => day1::run(part, &input),
-        2 => day2::run(part, &input),
-        3 => day3::run(part, &input),
-        4 => day4::run(part, &input),
-        5 => day5::run(part, &input),
-        6 => day6::run(part, &input),
-        7 => day7::run(part, &input),
-        8 => day8::run(part, &input),
-        9 => day9::run(part, &input),
-        10 => day10::run(part, &input),
-        11 => day11::run(part, &input),
-        12 => day12::run(part, &input),
-        13 => day13::run(part, &input),
-        14 => day14::run(part, &input),
-        15 => day15::run(part, &input),
-        16 => day16::run(part, &input),
-        _ => eprintln!("Day {} not implemented", day),
+// download a day's input into the input directory, unless we have it
+fn fetch(endpoint: &str, day: u32) -> bool {
+    let path = config::get().input_path(day);
+    if path.exists() {
+        eprintln!("Already have {}", path.display());
+        return false;
+    }
+    let session = match session() {
+        Some(session) => session,
+        None => {
+            eprintln!("Set AOC_SESSION or write the session cookie to .session");
+            return false;
+        }
     };
+    println!("Fetching day {} input to {}", day, path.display());
+    match submit::fetch(endpoint, &session, day) {
+        Ok(input) => {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, input).unwrap();
+            true
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            false
+        }
+    }
+}
+
+// run every implementation of a day on the same input, returns false if any
+// of them disagree.
+fn crosscheck(day: u32, parts: &[Part]) -> bool {
+    let input = read_input(day);
+    let mut agreed = true;
+    for &part in parts {
+        let variants = registry::variants(day, part);
+        if variants.is_empty() {
+            eprintln!("Day {} not implemented", day);
+            return false;
+        }
+        let mut expected: Option<String> = None;
+        for variant in variants {
+            progress::start(format!("Day {} Part {} [{}]", day, part, variant.name));
+            let (answer, elapsed, _usage) = measure(|| variant.solve(part, &input));
+            let (answer, verdict) = match answer {
+                Ok(answer) => {
+                    let verdict = match &expected {
+                        None => "",
+                        Some(e) if *e == answer.value => "ok",
+                        Some(_) => "MISMATCH",
+                    };
+                    (answer.value, verdict)
+                }
+                Err(e) => (format!("error: {}", e), "ERROR"),
+            };
+            println!(
+                "Day {} Part {} [{}] {} ({:.3}s) {}",
+                day,
+                part,
+                variant.name,
+                answer,
+                elapsed.as_secs_f64(),
+                verdict
+            );
+            match verdict {
+                // an implementation that can't answer is as bad as a wrong one
+                "MISMATCH" | "ERROR" => agreed = false,
+                "" => expected = Some(answer),
+                _ => (),
+            }
+        }
+    }
+    agreed
 }
 
+mod alloc;
+mod config;
+mod dashboard;
 mod day1;
 mod day10;
 mod day11;
@@ -59,7 +566,16 @@ mod day13;
 mod day14;
 mod day15;
 mod day16;
+mod day17;
+mod day18;
+mod day19;
 mod day2;
+mod day20;
+mod day21;
+mod day22;
+mod day23;
+mod day24;
+mod day25;
 mod day3;
 mod day4;
 mod day5;
@@ -67,3 +583,16 @@ mod day6;
 mod day7;
 mod day8;
 mod day9;
+mod grid;
+mod input;
+mod plugin;
+mod progress;
+#[cfg(test)]
+mod random;
+mod registry;
+mod search;
+mod sets;
+#[cfg(test)]
+mod snapshot;
+mod submit;
+mod watch;
@@ -0,0 +1,177 @@
+use super::registry::{Run, Variant};
+use super::{config, Answer, Part, Part::*};
+use libloading::Library;
+use std::env::consts::DLL_EXTENSION;
+use std::ffi::CStr;
+use std::fs;
+use std::os::raw::c_char;
+use std::path::Path;
+use std::sync::OnceLock;
+
+// Extra solvers can be loaded from shared libraries in the plugin directory
+// (see config.rs).  Each one shows up in the registry as a variant named
+// after its file, e.g. plugins/libtim.so gives a "tim" variant for every day
+// and part it solves.  A plugin exports these C functions:
+//
+//   uint32_t aoc_abi_version(void);
+//       must return ABI_VERSION
+//
+//   int32_t aoc_solves(uint32_t day, uint32_t part);
+//       non-zero if the plugin has a solution for the day and part (1 or 2)
+//
+//   const char *aoc_solve(uint32_t day, uint32_t part,
+//                         const char *input, size_t input_len);
+//       the answer as a NUL terminated string, which must stay valid until
+//       the next call, or NULL if it couldn't be solved.  The input is the
+//       normalized input file and is not NUL terminated.
+//
+// examples/plugin.rs is a plugin written in Rust.
+
+pub const ABI_VERSION: u32 = 1;
+
+type AbiVersion = unsafe extern "C" fn() -> u32;
+type Solves = unsafe extern "C" fn(u32, u32) -> i32;
+type Solve = unsafe extern "C" fn(u32, u32, *const c_char, usize) -> *const c_char;
+
+pub struct Plugin {
+    name: String,
+    solves: Solves,
+    solve: Solve,
+    // the functions above are only valid while this is loaded
+    _library: Library,
+}
+
+impl Plugin {
+    fn load(path: &Path) -> Result<Plugin, String> {
+        let name = plugin_name(path).ok_or("not a plugin")?;
+        unsafe {
+            let library = Library::new(path).map_err(|e| e.to_string())?;
+            let abi_version = *library
+                .get::<AbiVersion>(b"aoc_abi_version\0")
+                .map_err(|e| e.to_string())?;
+            if abi_version() != ABI_VERSION {
+                return Err(format!(
+                    "ABI version {}, expected {}",
+                    abi_version(),
+                    ABI_VERSION
+                ));
+            }
+            let solves = *library
+                .get::<Solves>(b"aoc_solves\0")
+                .map_err(|e| e.to_string())?;
+            let solve = *library
+                .get::<Solve>(b"aoc_solve\0")
+                .map_err(|e| e.to_string())?;
+            Ok(Plugin {
+                name,
+                solves,
+                solve,
+                _library: library,
+            })
+        }
+    }
+
+    fn solves(&self, day: u32, part: Part) -> bool {
+        unsafe { (self.solves)(day, part_number(part)) != 0 }
+    }
+
+    pub fn solve(&self, day: u32, part: Part, input: &str) -> Result<Answer, String> {
+        let answer = unsafe {
+            let answer = (self.solve)(
+                day,
+                part_number(part),
+                input.as_ptr() as *const c_char,
+                input.len(),
+            );
+            if answer.is_null() {
+                return Err(format!(
+                    "plugin {} failed on day {} part {}",
+                    self.name, day, part
+                ));
+            }
+            CStr::from_ptr(answer).to_string_lossy().into_owned()
+        };
+        Ok(Answer::new(answer))
+    }
+}
+
+fn part_number(part: Part) -> u32 {
+    match part {
+        One => 1,
+        Two => 2,
+    }
+}
+
+// libtim.so, libtim.dylib and tim.dll are all called "tim"
+fn plugin_name(path: &Path) -> Option<String> {
+    if path.extension()? != DLL_EXTENSION {
+        return None;
+    }
+    let stem = path.file_stem()?.to_str()?;
+    Some(stem.strip_prefix("lib").unwrap_or(stem).to_string())
+}
+
+static VARIANTS: OnceLock<Vec<Variant>> = OnceLock::new();
+
+// the variants from every plugin in the plugin directory, loaded the first
+// time they're asked for.  Plugins that fail to load are reported and
+// skipped.
+pub fn variants() -> &'static [Variant] {
+    VARIANTS.get_or_init(|| {
+        let mut paths: Vec<_> = match fs::read_dir(&config::get().plugin_dir) {
+            Ok(entries) => entries.map(|entry| entry.unwrap().path()).collect(),
+            Err(_) => return vec![],
+        };
+        paths.retain(|path| plugin_name(path).is_some());
+        paths.sort();
+
+        let mut variants = vec![];
+        for path in paths {
+            let plugin: &'static Plugin = match Plugin::load(&path) {
+                Ok(plugin) => Box::leak(Box::new(plugin)),
+                Err(e) => {
+                    eprintln!("Skipping plugin {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            let name: &'static str = Box::leak(plugin.name.clone().into_boxed_str());
+            for day in 1..=25 {
+                let parts: &'static [Part] =
+                    match (plugin.solves(day, One), plugin.solves(day, Two)) {
+                        (true, true) => &[One, Two],
+                        (true, false) => &[One],
+                        (false, true) => &[Two],
+                        (false, false) => continue,
+                    };
+                variants.push(Variant {
+                    day,
+                    name,
+                    parts,
+                    run: Run::Plugin(plugin),
+                    stream: None,
+                });
+            }
+        }
+        variants
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plugin_name() {
+        let file = |stem: &str| format!("{}.{}", stem, DLL_EXTENSION);
+        assert_eq!(
+            Some("tim".to_string()),
+            plugin_name(Path::new(&file("plugins/libtim")))
+        );
+        assert_eq!(
+            Some("tim".to_string()),
+            plugin_name(Path::new(&file("tim")))
+        );
+        assert_eq!(None, plugin_name(Path::new("plugins/README.txt")));
+        assert_eq!(None, plugin_name(Path::new("plugins/tim")));
+    }
+}
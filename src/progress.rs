@@ -0,0 +1,105 @@
+use std::fmt;
+use std::io::prelude::*;
+use std::io::{self, IsTerminal};
+use std::sync::atomic::{AtomicBool, Ordering::Relaxed};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// Long running solvers report what they're up to here, and if stderr is a
+// terminal it is shown as a status line that is redrawn at most every
+// INTERVAL.  Reporting is cheap but not free, so solvers should report once
+// per unit of coarse work (a row, a simulation) rather than every step.
+
+const INTERVAL: Duration = Duration::from_millis(100);
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static STATUS: Mutex<Status> = Mutex::new(Status {
+    label: String::new(),
+    drawn: None,
+});
+
+struct Status {
+    label: String,
+    drawn: Option<Instant>,
+}
+
+// turn on the status line if stderr is a terminal
+pub fn enable() {
+    ENABLED.store(io::stderr().is_terminal(), Relaxed);
+}
+
+// for when something else owns the terminal, like the dashboard
+pub fn disable() {
+    ENABLED.store(false, Relaxed);
+}
+
+// set what the status line is about, e.g. "Day 15 Part 2"
+pub fn start<T: fmt::Display>(label: T) {
+    if ENABLED.load(Relaxed) {
+        STATUS.lock().unwrap().label = label.to_string();
+    }
+}
+
+// e.g. report("elf power", 12)
+pub fn report<T: fmt::Display>(what: &str, value: T) {
+    if ENABLED.load(Relaxed) {
+        draw(|| format!("{} {}", what, value));
+    }
+}
+
+// e.g. report_of("row", 120, 300)
+pub fn report_of(what: &str, current: usize, total: usize) {
+    if ENABLED.load(Relaxed) {
+        draw(|| format_of(what, current, total));
+    }
+}
+
+// clear the status line before normal output is printed
+pub fn finish() {
+    if !ENABLED.load(Relaxed) {
+        return;
+    }
+    let mut status = STATUS.lock().unwrap();
+    if status.drawn.take().is_some() {
+        eprint!("\r\x1b[K");
+        io::stderr().flush().unwrap();
+    }
+}
+
+fn draw<F: FnOnce() -> String>(message: F) {
+    let mut status = STATUS.lock().unwrap();
+    let now = Instant::now();
+    if let Some(drawn) = status.drawn {
+        if now - drawn < INTERVAL {
+            return;
+        }
+    }
+    status.drawn = Some(now);
+    if status.label.is_empty() {
+        eprint!("\r\x1b[K{}", message());
+    } else {
+        eprint!("\r\x1b[K{}: {}", status.label, message());
+    }
+    io::stderr().flush().unwrap();
+}
+
+fn format_of(what: &str, current: usize, total: usize) -> String {
+    format!(
+        "{} {}/{} ({}%)",
+        what,
+        current,
+        total,
+        current * 100 / total.max(1)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_of() {
+        assert_eq!("row 120/300 (40%)", format_of("row", 120, 300));
+        assert_eq!("row 0/0 (0%)", format_of("row", 0, 0));
+    }
+}
@@ -0,0 +1,23 @@
+// A small xorshift generator for tests that check a fast solver against a
+// slow one on lots of made-up inputs.  It's seeded, so a failing input comes
+// back every run.
+
+pub struct XorShift {
+    state: u32,
+}
+
+impl XorShift {
+    // the seed mustn't be 0, which xorshift never leaves
+    pub fn new(seed: u32) -> XorShift {
+        assert_ne!(0, seed);
+        XorShift { state: seed }
+    }
+
+    // a number in 0..n
+    pub fn below(&mut self, n: u32) -> u32 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        self.state % n
+    }
+}
@@ -0,0 +1,216 @@
+use self::Run::*;
+use super::Part::*;
+use super::*;
+use std::io::BufRead;
+
+const BOTH: &[Part] = &[One, Two];
+
+// One implementation of a day's puzzle.  A day can have several, e.g. a
+// fast one and the naive one it can be checked against.
+pub struct Variant {
+    pub day: u32,
+    pub name: &'static str,
+    pub parts: &'static [Part],
+    pub run: Run,
+    // reads the input as it goes, for inputs too big to load into a String,
+    // though a part may still keep something the size of the input (day 1)
+    pub stream: Option<Stream>,
+}
+
+pub type Stream = fn(Part, &mut dyn BufRead) -> Result<Answer, String>;
+
+pub enum Run {
+    Builtin(fn(Part, &str) -> Answer),
+    // for solvers that can find an input has no answer, e.g. day 1's
+    // frequencies that never repeat
+    Fallible(fn(Part, &str) -> Result<Answer, String>),
+    Plugin(&'static plugin::Plugin),
+}
+
+impl Variant {
+    pub fn solves(&self, part: Part) -> bool {
+        self.parts.contains(&part)
+    }
+
+    pub fn solve(&self, part: Part, input: &str) -> Result<Answer, String> {
+        match self.run {
+            Builtin(run) => Ok(run(part, input)),
+            Fallible(run) => run(part, input),
+            Plugin(plugin) => plugin.solve(self.day, part, input),
+        }
+    }
+}
+
+// The first variant listed for a day and part is the one used by default.
+#[rustfmt::skip]
+static VARIANTS: &[Variant] = &[
+    Variant { day: 1, name: "default", parts: BOTH, run: Fallible(day1::run), stream: Some(day1::stream) },
+    Variant { day: 2, name: "default", parts: BOTH, run: Builtin(day2::run), stream: None },
+    Variant { day: 2, name: "families", parts: &[Two], run: Builtin(day2::run_families), stream: None },
+    Variant { day: 3, name: "default", parts: BOTH, run: Builtin(day3::run), stream: None },
+    Variant { day: 4, name: "default", parts: BOTH, run: Builtin(day4::run), stream: None },
+    Variant { day: 5, name: "default", parts: BOTH, run: Builtin(day5::run), stream: Some(day5::stream) },
+    Variant { day: 6, name: "flood", parts: &[One], run: Builtin(day6::run_flood), stream: None },
+    Variant { day: 6, name: "naive", parts: BOTH, run: Builtin(day6::run), stream: None },
+    Variant { day: 7, name: "default", parts: BOTH, run: Builtin(day7::run), stream: None },
+    Variant { day: 8, name: "default", parts: BOTH, run: Builtin(day8::run), stream: Some(day8::stream) },
+    Variant { day: 9, name: "default", parts: BOTH, run: Builtin(day9::run), stream: None },
+    Variant { day: 10, name: "default", parts: BOTH, run: Builtin(day10::run), stream: None },
+    Variant { day: 11, name: "default", parts: BOTH, run: Builtin(day11::run), stream: None },
+    Variant { day: 12, name: "default", parts: BOTH, run: Builtin(day12::run), stream: None },
+    Variant { day: 13, name: "default", parts: BOTH, run: Builtin(day13::run), stream: None },
+    Variant { day: 14, name: "default", parts: BOTH, run: Builtin(day14::run), stream: None },
+    Variant { day: 15, name: "default", parts: BOTH, run: Builtin(day15::run), stream: None },
+    Variant { day: 16, name: "default", parts: BOTH, run: Builtin(day16::run), stream: None },
+    Variant { day: 17, name: "default", parts: BOTH, run: Builtin(day17::run), stream: None },
+    Variant { day: 18, name: "default", parts: BOTH, run: Builtin(day18::run), stream: None },
+    Variant { day: 19, name: "default", parts: BOTH, run: Builtin(day19::run), stream: None },
+    Variant { day: 19, name: "naive", parts: &[One], run: Builtin(day19::run_naive), stream: None },
+    Variant { day: 20, name: "default", parts: BOTH, run: Builtin(day20::run), stream: None },
+    Variant { day: 21, name: "default", parts: BOTH, run: Builtin(day21::run), stream: None },
+    Variant { day: 21, name: "naive", parts: BOTH, run: Builtin(day21::run_naive), stream: None },
+    Variant { day: 22, name: "default", parts: BOTH, run: Builtin(day22::run), stream: None },
+    Variant { day: 23, name: "default", parts: BOTH, run: Builtin(day23::run), stream: None },
+    Variant { day: 24, name: "default", parts: BOTH, run: Builtin(day24::run), stream: None },
+    Variant { day: 25, name: "default", parts: &[One], run: Builtin(day25::run), stream: None },
+];
+
+// Draws a picture of a day's puzzle, as text.
+pub type Visualize = fn(&str) -> String;
+
+static VISUALIZATIONS: &[(u32, Visualize)] = &[
+    (10, day10::visualize),
+    (13, day13::visualize),
+    (15, day15::visualize),
+    (17, day17::visualize),
+    (20, day20::visualize),
+];
+
+pub fn visualization(day: u32) -> Option<Visualize> {
+    VISUALIZATIONS
+        .iter()
+        .find(|(d, _)| *d == day)
+        .map(|(_, visualize)| *visualize)
+}
+
+// Parses a day's input and throws the result away, so that parsing can be
+// measured apart from solving.  Days whose solvers work on the raw text
+// (2, 5, 11) have none.
+pub type Parse = fn(&str);
+
+static PARSERS: &[(u32, Parse)] = &[
+    (1, day1::parse),
+    (3, day3::parse),
+    (4, day4::parse),
+    (6, day6::parse),
+    (7, day7::parse),
+    (8, day8::parse),
+    (9, day9::parse),
+    (10, day10::parse),
+    (12, day12::parse),
+    (13, day13::parse),
+    (14, day14::parse),
+    (15, day15::parse),
+    (16, day16::parse),
+    (17, day17::parse),
+    (18, day18::parse),
+    (19, day19::parse),
+    (20, day20::parse),
+    (21, day19::parse),
+    (22, day22::parse),
+    (23, day23::parse),
+    (24, day24::parse),
+    (25, day25::parse),
+];
+
+pub fn parser(day: u32) -> Option<Parse> {
+    PARSERS
+        .iter()
+        .find(|(d, _)| *d == day)
+        .map(|(_, parse)| *parse)
+}
+
+// all variants that can solve the given day and part, default first and
+// any from plugins last
+pub fn variants(day: u32, part: Part) -> Vec<&'static Variant> {
+    VARIANTS
+        .iter()
+        .chain(plugin::variants())
+        .filter(|v| v.day == day && v.solves(part))
+        .collect()
+}
+
+// find a variant by name, or the default one if no name is given
+pub fn find(day: u32, part: Part, name: Option<&str>) -> Result<&'static Variant, String> {
+    let candidates = variants(day, part);
+    if candidates.is_empty() {
+        let other = match part {
+            One => Two,
+            Two => One,
+        };
+        return Err(if variants(day, other).is_empty() {
+            format!("Day {} not implemented", day)
+        } else {
+            format!("Day {} has no part {}", day, part)
+        });
+    }
+    match name {
+        None => Ok(candidates[0]),
+        Some(name) => candidates
+            .into_iter()
+            .find(|v| v.name == name)
+            .ok_or_else(|| {
+                format!(
+                    "Day {} part {} has no implementation called {} (try {})",
+                    day,
+                    part,
+                    name,
+                    names(day, part).join(", ")
+                )
+            }),
+    }
+}
+
+pub fn names(day: u32, part: Part) -> Vec<&'static str> {
+    variants(day, part).iter().map(|v| v.name).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find() {
+        assert_eq!("flood", find(6, One, None).unwrap().name);
+        assert_eq!("naive", find(6, Two, None).unwrap().name);
+        assert_eq!("naive", find(6, One, Some("naive")).unwrap().name);
+        assert!(find(6, Two, Some("flood")).is_err());
+        assert_eq!(
+            Err("Day 26 not implemented".to_string()),
+            find(26, One, None).map(|v| v.name)
+        );
+        assert_eq!(
+            Err("Day 25 has no part 2".to_string()),
+            find(25, Two, None).map(|v| v.name)
+        );
+    }
+
+    #[test]
+    fn test_solve_error() {
+        let variant = find(1, Two, None).unwrap();
+        assert_eq!(
+            "10",
+            variant.solve(Two, "+3\n+3\n+4\n-2\n-4\n").unwrap().value
+        );
+        assert!(variant.solve(Two, "+1\n+1\n").is_err());
+    }
+
+    #[test]
+    fn test_parser() {
+        assert!(parser(21).is_some());
+        assert!(parser(2).is_none());
+        for &(day, _) in PARSERS {
+            assert!(!variants(day, One).is_empty(), "day {}", day);
+        }
+    }
+}
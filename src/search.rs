@@ -0,0 +1,42 @@
+use super::progress;
+
+// Some part 2s want the smallest setting (attack power, a boost) that makes a
+// simulation come out a certain way.  The outcome isn't monotonic in the
+// setting, a little more power can change who attacks whom and how the fight
+// goes, so rather than bisecting every setting is tried in turn.
+
+// try each setting from `from` upwards, reporting it as `what`, until one
+// gives a result
+pub fn smallest_from<T, F>(from: u32, what: &str, mut attempt: F) -> (u32, T)
+where
+    F: FnMut(u32) -> Option<T>,
+{
+    let mut setting = from;
+    loop {
+        progress::report(what, setting);
+        if let Some(result) = attempt(setting) {
+            return (setting, result);
+        }
+        setting += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smallest_from() {
+        let mut tried = vec![];
+        let found = smallest_from(3, "n", |n| {
+            tried.push(n);
+            if n * n > 30 {
+                Some(n * n)
+            } else {
+                None
+            }
+        });
+        assert_eq!((6, 36), found);
+        assert_eq!(vec![3, 4, 5, 6], tried);
+    }
+}
@@ -0,0 +1,61 @@
+// Union-find over indexes, with path halving and union by size.
+pub struct DisjointSets {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    count: usize,
+}
+
+impl DisjointSets {
+    pub fn new(n: usize) -> DisjointSets {
+        DisjointSets {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            count: n,
+        }
+    }
+
+    // how many separate sets there are
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    pub fn union(&mut self, a: usize, b: usize) {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        self.count -= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union() {
+        let mut sets = DisjointSets::new(4);
+        assert_eq!(4, sets.count());
+        sets.union(0, 1);
+        sets.union(2, 3);
+        assert_eq!(2, sets.count());
+        sets.union(1, 0);
+        assert_eq!(2, sets.count());
+        sets.union(3, 0);
+        assert_eq!(1, sets.count());
+        assert_eq!(sets.find(1), sets.find(2));
+    }
+}
@@ -0,0 +1,85 @@
+// Golden file tests.  A test renders something (usually every step of a
+// simulation) to a string and compares it against snapshots/<name>.txt.
+// Run the tests with BLESS=1 to write the current output as the new golden
+// file, then review the change with git diff.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+fn path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("snapshots")
+        .join(format!("{}.txt", name))
+}
+
+pub fn assert_snapshot(name: &str, actual: &str) {
+    let path = path(name);
+    if env::var_os("BLESS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = match fs::read_to_string(&path) {
+        Ok(expected) => expected,
+        Err(e) => panic!(
+            "can't read {}: {} (run with BLESS=1 to create it)",
+            path.display(),
+            e
+        ),
+    };
+    if expected != actual {
+        panic!(
+            "output differs from {} (run with BLESS=1 to accept it)\n{}",
+            path.display(),
+            diff(&expected, actual)
+        );
+    }
+}
+
+// line based diff of the expected and actual output, showing only the
+// changed lines with the line number in the golden file
+fn diff(expected: &str, actual: &str) -> String {
+    let a: Vec<&str> = expected.lines().collect();
+    let b: Vec<&str> = actual.lines().collect();
+
+    // lcs[i][j] is the length of the longest common subsequence of a[i..]
+    // and b[j..]
+    let mut lcs = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut output = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            i += 1;
+            j += 1;
+        } else if j < b.len() && (i == a.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            output.push_str(&format!("{:>5} +{}\n", i + 1, b[j]));
+            j += 1;
+        } else {
+            output.push_str(&format!("{:>5} -{}\n", i + 1, a[i]));
+            i += 1;
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        assert_eq!("", diff("a\nb\n", "a\nb\n"));
+        assert_eq!("    2 -b\n    4 +x\n", diff("a\nb\nc\n", "a\nc\nx\n"));
+    }
+}
@@ -0,0 +1,482 @@
+use super::Part;
+use regex::Regex;
+use std::fs::{File, OpenOptions};
+use std::io::prelude::*;
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Submitting answers to the puzzle server.  Every attempt is appended to a
+// local history file so that we never resubmit an answer we know is wrong,
+// and so that the cooldown the server asks for survives between runs.
+
+pub const DEFAULT_ENDPOINT: &str = "https://adventofcode.com";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Verdict {
+    Right,
+    Wrong,
+    TooHigh,
+    TooLow,
+    TooSoon,
+    WrongLevel,
+    Unknown,
+}
+
+impl Verdict {
+    fn to_str(self) -> &'static str {
+        match self {
+            Verdict::Right => "right",
+            Verdict::Wrong => "wrong",
+            Verdict::TooHigh => "too-high",
+            Verdict::TooLow => "too-low",
+            Verdict::TooSoon => "too-soon",
+            Verdict::WrongLevel => "wrong-level",
+            Verdict::Unknown => "unknown",
+        }
+    }
+
+    fn from_str(s: &str) -> Verdict {
+        match s {
+            "right" => Verdict::Right,
+            "wrong" => Verdict::Wrong,
+            "too-high" => Verdict::TooHigh,
+            "too-low" => Verdict::TooLow,
+            "too-soon" => Verdict::TooSoon,
+            "wrong-level" => Verdict::WrongLevel,
+            _ => Verdict::Unknown,
+        }
+    }
+
+    fn is_wrong(self) -> bool {
+        self == Verdict::Wrong || self == Verdict::TooHigh || self == Verdict::TooLow
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Attempt {
+    pub time: u64,
+    pub day: u32,
+    pub part: Part,
+    pub answer: String,
+    pub verdict: Verdict,
+    // unix time before which the server won't accept another answer
+    pub wait_until: u64,
+}
+
+impl Attempt {
+    fn to_line(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\n",
+            self.time,
+            self.day,
+            self.part,
+            self.answer,
+            self.verdict.to_str(),
+            self.wait_until
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Attempt> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() != 6 {
+            return None;
+        }
+        Some(Attempt {
+            time: fields[0].parse().ok()?,
+            day: fields[1].parse().ok()?,
+            part: match fields[2] {
+                "1" => Part::One,
+                "2" => Part::Two,
+                _ => return None,
+            },
+            answer: fields[3].to_string(),
+            verdict: Verdict::from_str(fields[4]),
+            wait_until: fields[5].parse().ok()?,
+        })
+    }
+}
+
+// the history is tab separated, one attempt a line
+fn fits_history(answer: &str) -> bool {
+    !answer.contains(['\t', '\n', '\r'])
+}
+
+pub struct History {
+    path: PathBuf,
+    attempts: Vec<Attempt>,
+}
+
+impl History {
+    // a missing file is an empty history
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<History> {
+        let path = path.as_ref().to_path_buf();
+        let mut attempts = vec![];
+        match File::open(&path) {
+            Ok(f) => {
+                for line in BufReader::new(f).lines() {
+                    if let Some(attempt) = Attempt::from_line(&line?) {
+                        attempts.push(attempt);
+                    }
+                }
+            }
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => (),
+            Err(e) => return Err(e),
+        }
+        Ok(History { path, attempts })
+    }
+
+    pub fn record(&mut self, attempt: Attempt) -> io::Result<()> {
+        if !fits_history(&attempt.answer) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{:?} would break the history's lines", attempt.answer),
+            ));
+        }
+        let mut f = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        f.write_all(attempt.to_line().as_bytes())?;
+        self.attempts.push(attempt);
+        Ok(())
+    }
+
+    // the answer the server accepted, if any
+    pub fn right_answer(&self, day: u32, part: Part) -> Option<&str> {
+        self.attempts
+            .iter()
+            .find(|a| a.day == day && a.part == part && a.verdict == Verdict::Right)
+            .map(|a| a.answer.as_str())
+    }
+
+    // the answer sent most recently, whatever the server made of it
+    pub fn last_answer(&self, day: u32, part: Part) -> Option<&str> {
+        self.attempts
+            .iter()
+            .rev()
+            .find(|a| a.day == day && a.part == part)
+            .map(|a| a.answer.as_str())
+    }
+
+    // Err with the reason if this answer shouldn't be sent to the server
+    pub fn check(&self, day: u32, part: Part, answer: &str, now: u64) -> Result<(), String> {
+        if !fits_history(answer) {
+            return Err(format!("{:?} has a tab or line break in it", answer));
+        }
+        if let Some(wait_until) = self.attempts.iter().map(|a| a.wait_until).max() {
+            if wait_until > now {
+                return Err(format!(
+                    "The server asked us to wait, try again in {}s",
+                    wait_until - now
+                ));
+            }
+        }
+        for attempt in self
+            .attempts
+            .iter()
+            .filter(|a| a.day == day && a.part == part)
+        {
+            if attempt.verdict == Verdict::Right {
+                return Err(format!(
+                    "Day {} part {} was already solved with {}",
+                    day, part, attempt.answer
+                ));
+            }
+            if attempt.verdict.is_wrong() && attempt.answer == answer {
+                return Err(format!("{} is already known to be wrong", answer));
+            }
+            if let (Ok(tried), Ok(answer)) = (attempt.answer.parse::<i64>(), answer.parse::<i64>())
+            {
+                if attempt.verdict == Verdict::TooHigh && answer >= tried {
+                    return Err(format!("{} is too high, {} already was", answer, tried));
+                }
+                if attempt.verdict == Verdict::TooLow && answer <= tried {
+                    return Err(format!("{} is too low, {} already was", answer, tried));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// work out the verdict from the server's response page, and when the next
+// answer can be sent
+pub fn parse_response(body: &str, now: u64) -> (Verdict, u64) {
+    let verdict = if body.contains("That's the right answer") {
+        Verdict::Right
+    } else if body.contains("That's not the right answer") {
+        if body.contains("your answer is too high") {
+            Verdict::TooHigh
+        } else if body.contains("your answer is too low") {
+            Verdict::TooLow
+        } else {
+            Verdict::Wrong
+        }
+    } else if body.contains("You gave an answer too recently") {
+        Verdict::TooSoon
+    } else if body.contains("You don't seem to be solving the right level") {
+        Verdict::WrongLevel
+    } else {
+        Verdict::Unknown
+    };
+
+    let left = Regex::new(r"You have (?:(\d+)m )?(\d+)s left to wait").unwrap();
+    let minutes = Regex::new(r"(?i)wait (one|\d+) minutes? before trying again").unwrap();
+    let wait = if let Some(cap) = left.captures(body) {
+        let m: u64 = cap.get(1).map_or(0, |m| m.as_str().parse().unwrap());
+        let s: u64 = cap[2].parse().unwrap();
+        m * 60 + s
+    } else if let Some(cap) = minutes.captures(body) {
+        match &cap[1] {
+            "one" => 60,
+            n => n.parse::<u64>().unwrap() * 60,
+        }
+    } else {
+        0
+    };
+    (verdict, if wait > 0 { now + wait } else { 0 })
+}
+
+// post an answer with curl, returning the response page
+pub fn post(
+    endpoint: &str,
+    session: &str,
+    day: u32,
+    part: Part,
+    answer: &str,
+) -> Result<String, String> {
+    let url = format!("{}/2018/day/{}/answer", endpoint.trim_end_matches('/'), day);
+    let args = [
+        "--data".to_string(),
+        format!("level={}", part),
+        "--data-urlencode".to_string(),
+        format!("answer={}", answer),
+    ];
+    curl(session, &args, &url)
+}
+
+// download a day's puzzle input
+pub fn fetch(endpoint: &str, session: &str, day: u32) -> Result<String, String> {
+    let url = format!("{}/2018/day/{}/input", endpoint.trim_end_matches('/'), day);
+    curl(session, &["--fail".to_string()], &url)
+}
+
+// run curl with our session cookie, returning the response body
+fn curl(session: &str, args: &[String], url: &str) -> Result<String, String> {
+    // the cookie goes in via stdin so it doesn't show up in the process list
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--max-time", "30"])
+        .args(["--config", "-"])
+        .args(args)
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Could not run curl: {}", e))?;
+    writeln!(
+        child.stdin.take().unwrap(),
+        "cookie = \"session={}\"",
+        session
+    )
+    .map_err(|e| format!("Could not run curl: {}", e))?;
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Could not run curl: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Request to {} failed: {}",
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+// check the history, send the answer and record what the server said
+pub fn submit(
+    history: &mut History,
+    endpoint: &str,
+    session: &str,
+    day: u32,
+    part: Part,
+    answer: &str,
+) -> Result<Verdict, String> {
+    let now = now();
+    history.check(day, part, answer, now)?;
+    let body = post(endpoint, session, day, part, answer)?;
+    let (verdict, wait_until) = parse_response(&body, now);
+    history
+        .record(Attempt {
+            time: now,
+            day,
+            part,
+            answer: answer.to_string(),
+            verdict,
+            wait_until,
+        })
+        .map_err(|e| format!("Could not record attempt: {}", e))?;
+    Ok(verdict)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+    use std::net::TcpListener;
+    use std::thread;
+
+    fn attempt(part: Part, answer: &str, verdict: Verdict, wait_until: u64) -> Attempt {
+        Attempt {
+            time: 1000,
+            day: 1,
+            part,
+            answer: answer.to_string(),
+            verdict,
+            wait_until,
+        }
+    }
+
+    fn temp_history(name: &str) -> History {
+        let path = env::temp_dir().join(format!("aoc-{}-{}.tsv", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        History::load(path).unwrap()
+    }
+
+    // serve one canned response on a local port, returning the endpoint and
+    // a handle to get the request that was received
+    fn stand_in(body: &'static str) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = vec![0; 4096];
+            let len = stream.read(&mut request).unwrap();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+            String::from_utf8_lossy(&request[..len]).into_owned()
+        });
+        (endpoint, handle)
+    }
+
+    #[test]
+    fn test_parse_response() {
+        assert_eq!(
+            (Verdict::Right, 0),
+            parse_response(
+                "<p>That's the right answer!  You are one gold star closer",
+                100
+            )
+        );
+        assert_eq!(
+            (Verdict::TooHigh, 160),
+            parse_response(
+                "<p>That's not the right answer; your answer is too high.  \
+                 Please wait one minute before trying again.",
+                100
+            )
+        );
+        assert_eq!(
+            (Verdict::Wrong, 400),
+            parse_response(
+                "That's not the right answer.  please wait 5 minutes before trying again.",
+                100
+            )
+        );
+        assert_eq!(
+            (Verdict::TooSoon, 163),
+            parse_response(
+                "You gave an answer too recently; you have to wait after submitting an \
+                 answer before trying again.  You have 1m 3s left to wait.",
+                100
+            )
+        );
+        assert_eq!(
+            (Verdict::WrongLevel, 0),
+            parse_response("You don't seem to be solving the right level.", 100)
+        );
+    }
+
+    #[test]
+    fn test_attempt_line() {
+        let a = attempt(Part::Two, "abc", Verdict::TooLow, 1060);
+        assert_eq!("1000\t1\t2\tabc\ttoo-low\t1060\n", a.to_line());
+        assert_eq!(
+            Some(a),
+            Attempt::from_line("1000\t1\t2\tabc\ttoo-low\t1060")
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let mut history = temp_history("check");
+        history
+            .record(attempt(Part::One, "500", Verdict::TooHigh, 1060))
+            .unwrap();
+        history
+            .record(attempt(Part::One, "abc", Verdict::Wrong, 0))
+            .unwrap();
+        assert!(history.check(1, Part::One, "400", 1030).is_err());
+        assert!(history.check(1, Part::One, "400", 1060).is_ok());
+        assert!(history.check(1, Part::One, "abc", 2000).is_err());
+        assert!(history.check(1, Part::One, "500", 2000).is_err());
+        assert!(history.check(1, Part::One, "501", 2000).is_err());
+        assert!(history.check(1, Part::Two, "500", 2000).is_ok());
+        assert_eq!(None, history.right_answer(1, Part::One));
+        assert_eq!(Some("abc"), history.last_answer(1, Part::One));
+        assert_eq!(None, history.last_answer(1, Part::Two));
+
+        history
+            .record(attempt(Part::One, "450", Verdict::Right, 0))
+            .unwrap();
+        assert!(history.check(1, Part::One, "400", 2000).is_err());
+        assert_eq!(Some("450"), history.right_answer(1, Part::One));
+        assert_eq!(Some("450"), history.last_answer(1, Part::One));
+        assert!(history.check(1, Part::Two, "4\t5", 2000).is_err());
+        assert!(history.check(1, Part::Two, "45\n", 2000).is_err());
+        assert!(history
+            .record(attempt(Part::Two, "4\t5", Verdict::Wrong, 0))
+            .is_err());
+
+        let reloaded = History::load(&history.path).unwrap();
+        assert_eq!(history.attempts, reloaded.attempts);
+        fs::remove_file(&history.path).unwrap();
+    }
+
+    #[test]
+    fn test_submit() {
+        let mut history = temp_history("submit");
+        let (endpoint, handle) = stand_in(
+            "<article><p>That's not the right answer; your answer is too low.  \
+             Please wait one minute before trying again.</p></article>",
+        );
+        let verdict = submit(&mut history, &endpoint, "token", 3, Part::Two, "42");
+        assert_eq!(Ok(Verdict::TooLow), verdict);
+        let request = handle.join().unwrap();
+        assert!(request.starts_with("POST /2018/day/3/answer "));
+        assert!(request.contains("session=token"));
+        assert_eq!(1, history.attempts.len());
+        assert!(history.attempts[0].wait_until > history.attempts[0].time);
+
+        // refused locally without contacting the server (there's no one
+        // listening any more)
+        assert!(submit(&mut history, &endpoint, "token", 3, Part::Two, "41").is_err());
+        assert_eq!(1, history.attempts.len());
+        fs::remove_file(&history.path).unwrap();
+    }
+}
@@ -0,0 +1,136 @@
+use super::{config, input, measure, progress, registry, Part};
+use std::any::Any;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+// Polls an input file and reruns a day's solvers every time it is saved.
+// Solvers (and the parsers inside them) report bad input by panicking, so
+// each run is wrapped in catch_unwind and the panic shown as the result
+// instead of ending the watch.
+
+const INTERVAL: Duration = Duration::from_millis(250);
+
+// what we compare to notice a save; the length catches rewrites within the
+// same mtime tick
+#[derive(Debug, PartialEq)]
+struct Stamp {
+    modified: SystemTime,
+    len: u64,
+}
+
+fn stamp(path: &Path) -> Option<Stamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some(Stamp {
+        modified: metadata.modified().ok()?,
+        len: metadata.len(),
+    })
+}
+
+// the most recent panic message and location, stashed by our panic hook
+static PANIC: Mutex<Option<String>> = Mutex::new(None);
+
+pub fn watch(day: u32, parts: &[Part], name: Option<&str>, path: &Path) {
+    let mut variants = vec![];
+    for &part in parts {
+        match registry::find(day, part, name) {
+            Ok(variant) => variants.push((part, variant)),
+            Err(e) => {
+                eprintln!("{}", e);
+                return;
+            }
+        }
+    }
+
+    catch_panics();
+    println!("Watching {} (Ctrl-C to stop)", path.display());
+    let mut last = None;
+    loop {
+        let current = stamp(path);
+        if current != last {
+            if current.is_none() {
+                println!("Waiting for {}", path.display());
+            } else {
+                println!("--- {} ---", chrono::Local::now().format("%H:%M:%S"));
+                match fs::read_to_string(path) {
+                    Ok(input) => {
+                        let input = input::normalize(&input, config::get().trim_lines);
+                        for &(part, variant) in &variants {
+                            progress::start(format!("Day {} Part {}", day, part));
+                            let (result, elapsed, _usage) =
+                                measure(|| guard(|| variant.solve(part, &input)).and_then(|r| r));
+                            match result {
+                                Ok(answer) => println!(
+                                    "Day {} Part {}: {} ({:.3}s)",
+                                    day,
+                                    part,
+                                    answer,
+                                    elapsed.as_secs_f64()
+                                ),
+                                Err(e) => println!("Day {} Part {}: error: {}", day, part, e),
+                            }
+                        }
+                    }
+                    Err(e) => println!("Can't read {}: {}", path.display(), e),
+                }
+            }
+            last = current;
+        }
+        thread::sleep(INTERVAL);
+    }
+}
+
+// keep the default panic hook's output from scribbling over ours, and
+// remember the message and location for guard()
+pub fn catch_panics() {
+    panic::set_hook(Box::new(|info| {
+        *PANIC.lock().unwrap() = Some(info.to_string());
+    }));
+}
+
+// run f, turning a panic into an error message
+pub fn guard<T, F: FnOnce() -> T>(f: F) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        PANIC
+            .lock()
+            .unwrap()
+            .take()
+            .unwrap_or_else(|| panic_message(&*payload))
+    })
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "panicked".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guard() {
+        assert_eq!(Ok(3), guard(|| 1 + 2));
+        let e = guard(|| "x".parse::<i32>().unwrap()).unwrap_err();
+        assert!(e.contains("InvalidDigit"), "{}", e);
+    }
+
+    #[test]
+    fn test_stamp() {
+        let path = std::env::temp_dir().join(format!("aoc-watch-{}.txt", std::process::id()));
+        assert_eq!(None, stamp(&path));
+        fs::write(&path, "1\n").unwrap();
+        let first = stamp(&path).unwrap();
+        fs::write(&path, "1\n2\n").unwrap();
+        assert_ne!(first, stamp(&path).unwrap());
+        fs::remove_file(&path).unwrap();
+    }
+}